@@ -0,0 +1,318 @@
+use crate::constants;
+
+use super::{dmc::Dmc, noise::Noise, pulse::Pulse, triangle::Triangle};
+
+// Frame counter steps in CPU cycles (NTSC)
+const QUARTER_FRAME_1: u32 = 7457;
+const HALF_FRAME_1: u32 = 14913;
+const QUARTER_FRAME_3: u32 = 22371;
+const FOUR_STEP_END: u32 = 29829;
+const FIVE_STEP_HALF_FRAME_2: u32 = 37281;
+
+// Raw channel levels before mixing
+#[derive(Clone, Copy)]
+pub struct ChannelOutput {
+    pub pulse_1: u8,
+    pub pulse_2: u8,
+    pub triangle: u8,
+    pub noise: u8,
+    pub dmc: u8,
+}
+
+pub struct Apu {
+    pulse_1: Pulse,
+    pulse_2: Pulse,
+    triangle: Triangle,
+    noise: Noise,
+    dmc: Dmc,
+
+    five_step_mode: bool,
+    irq_inhibit: bool,
+    frame_irq: bool,
+    frame_cycle: u32,
+    // Writes to $4017 reset the sequencer 3 or 4 CPU cycles later
+    frame_reset_delay: u8,
+
+    // Pulse timers run at half the CPU rate, triangle, noise and DMC every cycle
+    odd_cycle: bool,
+}
+
+impl Apu {
+    pub fn new() -> Apu {
+        Apu {
+            pulse_1: Pulse::new(true),
+            pulse_2: Pulse::new(false),
+            triangle: Triangle::new(),
+            noise: Noise::new(),
+            dmc: Dmc::new(),
+            five_step_mode: false,
+            irq_inhibit: false,
+            frame_irq: false,
+            frame_cycle: 0,
+            frame_reset_delay: 0,
+            odd_cycle: false,
+        }
+    }
+
+    pub fn cpu_write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x4000..=0x4003 => self.pulse_1.write(addr & 0x0003, data),
+            0x4004..=0x4007 => self.pulse_2.write(addr & 0x0003, data),
+            0x4008..=0x400B => self.triangle.write(addr & 0x0003, data),
+            0x400C..=0x400F => self.noise.write(addr & 0x0003, data),
+            0x4010..=0x4013 => self.dmc.write(addr & 0x0003, data),
+            constants::apu::STATUS_ADDR => {
+                self.pulse_1.length_counter.set_enabled((data & 0x01) != 0);
+                self.pulse_2.length_counter.set_enabled((data & 0x02) != 0);
+                self.triangle.length_counter.set_enabled((data & 0x04) != 0);
+                self.noise.length_counter.set_enabled((data & 0x08) != 0);
+                self.dmc.set_enabled((data & 0x10) != 0);
+            }
+            constants::apu::FRAME_COUNTER_ADDR => {
+                self.five_step_mode = (data & 0x80) != 0;
+                self.irq_inhibit = (data & 0x40) != 0;
+                if self.irq_inhibit {
+                    self.frame_irq = false;
+                }
+                self.frame_reset_delay = if self.odd_cycle { 4 } else { 3 };
+            }
+            _ => {}
+        }
+    }
+
+    // $4015: IF-D NT21, reading acknowledges the frame interrupt
+    pub fn read_status(&mut self, readonly: bool) -> u8 {
+        let mut status = 0x00;
+        if self.pulse_1.length_counter.active() {
+            status |= 0x01;
+        }
+        if self.pulse_2.length_counter.active() {
+            status |= 0x02;
+        }
+        if self.triangle.length_counter.active() {
+            status |= 0x04;
+        }
+        if self.noise.length_counter.active() {
+            status |= 0x08;
+        }
+        if self.dmc.active() {
+            status |= 0x10;
+        }
+        if self.frame_irq {
+            status |= 0x40;
+        }
+        if self.dmc.irq {
+            status |= 0x80;
+        }
+
+        if !readonly {
+            self.frame_irq = false;
+        }
+        status
+    }
+
+    // Called once per CPU cycle
+    pub fn clock(&mut self) {
+        self.triangle.clock_timer();
+        self.noise.clock_timer();
+        self.dmc.clock_timer();
+        if self.odd_cycle {
+            self.pulse_1.clock_timer();
+            self.pulse_2.clock_timer();
+        }
+
+        self.clock_frame_counter();
+        self.odd_cycle = !self.odd_cycle;
+    }
+
+    pub fn frame_irq(&self) -> bool {
+        self.frame_irq
+    }
+
+    pub fn dmc_irq(&self) -> bool {
+        self.dmc.irq
+    }
+
+    // The DMC fetches its samples through the CPU bus, stalling the CPU
+    pub fn dmc_dma_address(&self) -> Option<u16> {
+        self.dmc.dma_address()
+    }
+
+    pub fn dmc_dma_complete(&mut self, data: u8) {
+        self.dmc.fill_sample_buffer(data);
+    }
+
+    pub fn channel_output(&self) -> ChannelOutput {
+        ChannelOutput {
+            pulse_1: self.pulse_1.output(),
+            pulse_2: self.pulse_2.output(),
+            triangle: self.triangle.output(),
+            noise: self.noise.output(),
+            dmc: self.dmc.output(),
+        }
+    }
+
+    fn clock_frame_counter(&mut self) {
+        if self.frame_reset_delay > 0 {
+            self.frame_reset_delay -= 1;
+            if self.frame_reset_delay == 0 {
+                self.frame_cycle = 0;
+                // Entering the 5-step mode clocks all units immediately
+                if self.five_step_mode {
+                    self.clock_quarter_frame();
+                    self.clock_half_frame();
+                }
+            }
+        }
+
+        self.frame_cycle += 1;
+        match self.frame_cycle {
+            QUARTER_FRAME_1 | QUARTER_FRAME_3 => self.clock_quarter_frame(),
+            HALF_FRAME_1 => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+            }
+            // The 4-step sequence raises its IRQ on the last three cycles
+            c if !self.five_step_mode && c == FOUR_STEP_END - 1 => self.set_frame_irq(),
+            FOUR_STEP_END if !self.five_step_mode => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+                self.set_frame_irq();
+            }
+            c if !self.five_step_mode && c == FOUR_STEP_END + 1 => {
+                self.set_frame_irq();
+                self.frame_cycle = 0;
+            }
+            FIVE_STEP_HALF_FRAME_2 if self.five_step_mode => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+            }
+            c if self.five_step_mode && c == FIVE_STEP_HALF_FRAME_2 + 1 => self.frame_cycle = 0,
+            _ => {}
+        }
+    }
+
+    fn set_frame_irq(&mut self) {
+        if !self.irq_inhibit {
+            self.frame_irq = true;
+        }
+    }
+
+    fn clock_quarter_frame(&mut self) {
+        self.pulse_1.envelope.clock();
+        self.pulse_2.envelope.clock();
+        self.noise.envelope.clock();
+        self.triangle.clock_linear_counter();
+    }
+
+    fn clock_half_frame(&mut self) {
+        self.pulse_1.length_counter.clock();
+        self.pulse_2.length_counter.clock();
+        self.triangle.length_counter.clock();
+        self.noise.length_counter.clock();
+        self.pulse_1.clock_sweep();
+        self.pulse_2.clock_sweep();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(apu: &mut Apu, cycles: u32) {
+        for _ in 0..cycles {
+            apu.clock();
+        }
+    }
+
+    // Pulse 1 with a length of 2 half frames
+    fn apu_with_short_pulse() -> Apu {
+        let mut apu = Apu::new();
+        apu.cpu_write(constants::apu::STATUS_ADDR, 0x01);
+        apu.cpu_write(0x4003, 0x18);
+        apu
+    }
+
+    #[test]
+    fn four_step_irq_on_the_last_cycles() {
+        let mut apu = Apu::new();
+        clock(&mut apu, FOUR_STEP_END - 2);
+        assert!(!apu.frame_irq());
+        clock(&mut apu, 1);
+        assert!(apu.frame_irq());
+
+        // Peeking doesn't acknowledge, reading does
+        assert_eq!(apu.read_status(true) & 0x40, 0x40);
+        assert!(apu.frame_irq());
+        apu.read_status(false);
+        assert!(!apu.frame_irq());
+
+        // Raised again on the following two cycles
+        clock(&mut apu, 1);
+        assert!(apu.frame_irq());
+        apu.read_status(false);
+        clock(&mut apu, 1);
+        assert!(apu.frame_irq());
+        apu.read_status(false);
+        clock(&mut apu, 1);
+        assert!(!apu.frame_irq());
+    }
+
+    #[test]
+    fn irq_inhibit_suppresses_and_clears_the_irq() {
+        let mut apu = Apu::new();
+        clock(&mut apu, FOUR_STEP_END);
+        assert!(apu.frame_irq());
+
+        apu.cpu_write(constants::apu::FRAME_COUNTER_ADDR, 0x40);
+        assert!(!apu.frame_irq());
+        clock(&mut apu, 2 * FOUR_STEP_END);
+        assert!(!apu.frame_irq());
+    }
+
+    #[test]
+    fn four_step_half_frames() {
+        let mut apu = apu_with_short_pulse();
+        clock(&mut apu, HALF_FRAME_1);
+        assert_eq!(apu.read_status(true) & 0x01, 0x01);
+        clock(&mut apu, FOUR_STEP_END - HALF_FRAME_1);
+        assert_eq!(apu.read_status(true) & 0x01, 0x00);
+    }
+
+    #[test]
+    fn five_step_mode_clocks_immediately_without_irq() {
+        let mut apu = apu_with_short_pulse();
+        apu.cpu_write(constants::apu::FRAME_COUNTER_ADDR, 0x80);
+
+        // The write takes effect 3 cycles later on an even cycle
+        clock(&mut apu, 3);
+        assert_eq!(apu.read_status(true) & 0x01, 0x01);
+        clock(&mut apu, HALF_FRAME_1);
+        assert_eq!(apu.read_status(true) & 0x01, 0x00);
+
+        clock(&mut apu, FIVE_STEP_HALF_FRAME_2);
+        assert!(!apu.frame_irq());
+    }
+
+    #[test]
+    fn frame_counter_write_delay_depends_on_parity() {
+        let cycles_until_irq = |apu: &mut Apu| {
+            apu.cpu_write(constants::apu::FRAME_COUNTER_ADDR, 0x00);
+            let mut cycles = 0;
+            while !apu.frame_irq() {
+                apu.clock();
+                cycles += 1;
+            }
+            cycles
+        };
+
+        // The reset lands on the 3rd cycle after the write, which is the sequence's first
+        let mut even = Apu::new();
+        assert_eq!(cycles_until_irq(&mut even), 2 + FOUR_STEP_END - 1);
+
+        // A write on an odd cycle waits one more
+        let mut odd = Apu::new();
+        clock(&mut odd, 1);
+        assert_eq!(cycles_until_irq(&mut odd), 3 + FOUR_STEP_END - 1);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod apu;
+mod dmc;
+mod envelope;
+mod length_counter;
+mod noise;
+mod pulse;
+mod triangle;
@@ -0,0 +1,71 @@
+use super::{envelope::Envelope, length_counter::LengthCounter};
+
+// Timer periods in CPU cycles (NTSC)
+const PERIOD_TABLE: [u16; 16] = [
+    4, 8, 16, 32, 64, 96, 128, 160, 202, 254, 380, 508, 762, 1016, 2034, 4068,
+];
+
+pub struct Noise {
+    pub envelope: Envelope,
+    pub length_counter: LengthCounter,
+
+    timer: u16,
+    timer_period: u16,
+    // Short mode taps bit 6 instead of bit 1, giving a 93 step metallic tone
+    short_mode: bool,
+    shift_register: u16,
+}
+
+impl Noise {
+    pub fn new() -> Noise {
+        Noise {
+            envelope: Envelope::new(),
+            length_counter: LengthCounter::new(),
+            timer: 0,
+            timer_period: PERIOD_TABLE[0],
+            short_mode: false,
+            shift_register: 0x0001,
+        }
+    }
+
+    pub fn write(&mut self, register: u16, data: u8) {
+        match register {
+            // --LC VVVV
+            0 => {
+                self.length_counter.halt = (data & 0x20) != 0;
+                self.envelope.write(data);
+            }
+            1 => {}
+            // M--- PPPP
+            2 => {
+                self.short_mode = (data & 0x80) != 0;
+                self.timer_period = PERIOD_TABLE[(data & 0x0F) as usize];
+            }
+            // LLLL L---
+            _ => {
+                self.length_counter.load(data);
+                self.envelope.start = true;
+            }
+        }
+    }
+
+    // Clocked every CPU cycle
+    pub fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+        self.timer = self.timer_period - 1;
+
+        let tap = if self.short_mode { 6 } else { 1 };
+        let feedback = (self.shift_register ^ (self.shift_register >> tap)) & 0x0001;
+        self.shift_register = (self.shift_register >> 1) | (feedback << 14);
+    }
+
+    pub fn output(&self) -> u8 {
+        if (self.shift_register & 0x0001) != 0 || !self.length_counter.active() {
+            return 0;
+        }
+        self.envelope.output()
+    }
+}
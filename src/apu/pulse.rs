@@ -0,0 +1,125 @@
+use super::{envelope::Envelope, length_counter::LengthCounter};
+
+const DUTY_TABLE: [[u8; 8]; 4] = [
+    [0, 1, 0, 0, 0, 0, 0, 0],
+    [0, 1, 1, 0, 0, 0, 0, 0],
+    [0, 1, 1, 1, 1, 0, 0, 0],
+    [1, 0, 0, 1, 1, 1, 1, 1],
+];
+
+pub struct Pulse {
+    // Pulse 1 negates its sweep with ones' complement, pulse 2 with two's complement
+    ones_complement: bool,
+
+    pub envelope: Envelope,
+    pub length_counter: LengthCounter,
+
+    duty: u8,
+    sequence: u8,
+    timer: u16,
+    timer_period: u16,
+
+    sweep_enabled: bool,
+    sweep_period: u8,
+    sweep_negate: bool,
+    sweep_shift: u8,
+    sweep_divider: u8,
+    sweep_reload: bool,
+}
+
+impl Pulse {
+    pub fn new(ones_complement: bool) -> Pulse {
+        Pulse {
+            ones_complement,
+            envelope: Envelope::new(),
+            length_counter: LengthCounter::new(),
+            duty: 0,
+            sequence: 0,
+            timer: 0,
+            timer_period: 0,
+            sweep_enabled: false,
+            sweep_period: 0,
+            sweep_negate: false,
+            sweep_shift: 0,
+            sweep_divider: 0,
+            sweep_reload: false,
+        }
+    }
+
+    pub fn write(&mut self, register: u16, data: u8) {
+        match register {
+            // DDLC VVVV
+            0 => {
+                self.duty = data >> 6;
+                self.length_counter.halt = (data & 0x20) != 0;
+                self.envelope.write(data);
+            }
+            // EPPP NSSS
+            1 => {
+                self.sweep_enabled = (data & 0x80) != 0;
+                self.sweep_period = (data >> 4) & 0x07;
+                self.sweep_negate = (data & 0x08) != 0;
+                self.sweep_shift = data & 0x07;
+                self.sweep_reload = true;
+            }
+            2 => self.timer_period = (self.timer_period & 0x0700) | data as u16,
+            // LLLL LTTT
+            _ => {
+                self.timer_period = (self.timer_period & 0x00FF) | ((data & 0x07) as u16) << 8;
+                self.length_counter.load(data);
+                self.envelope.start = true;
+                self.sequence = 0;
+            }
+        }
+    }
+
+    // Clocked every APU cycle, i.e. every other CPU cycle
+    pub fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            self.sequence = (self.sequence + 1) & 0x07;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn clock_sweep(&mut self) {
+        if self.sweep_divider == 0 && self.sweep_enabled && self.sweep_shift > 0 && !self.muted() {
+            self.timer_period = self.sweep_target();
+        }
+        if self.sweep_divider == 0 || self.sweep_reload {
+            self.sweep_divider = self.sweep_period;
+            self.sweep_reload = false;
+        } else {
+            self.sweep_divider -= 1;
+        }
+    }
+
+    fn sweep_target(&self) -> u16 {
+        let delta = self.timer_period >> self.sweep_shift;
+        if !self.sweep_negate {
+            return self.timer_period + delta;
+        }
+        let delta = if self.ones_complement {
+            delta + 1
+        } else {
+            delta
+        };
+        self.timer_period.saturating_sub(delta)
+    }
+
+    // The sweep unit mutes the channel even while disabled
+    fn muted(&self) -> bool {
+        self.timer_period < 8 || self.sweep_target() > 0x07FF
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.muted()
+            || !self.length_counter.active()
+            || DUTY_TABLE[self.duty as usize][self.sequence as usize] == 0
+        {
+            return 0;
+        }
+        self.envelope.output()
+    }
+}
@@ -0,0 +1,82 @@
+use super::length_counter::LengthCounter;
+
+const SEQUENCE: [u8; 32] = [
+    15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12,
+    13, 14, 15,
+];
+
+pub struct Triangle {
+    pub length_counter: LengthCounter,
+
+    sequence: u8,
+    timer: u16,
+    timer_period: u16,
+
+    // Also halts the length counter
+    control: bool,
+    linear_counter: u8,
+    linear_reload_value: u8,
+    linear_reload: bool,
+}
+
+impl Triangle {
+    pub fn new() -> Triangle {
+        Triangle {
+            length_counter: LengthCounter::new(),
+            sequence: 0,
+            timer: 0,
+            timer_period: 0,
+            control: false,
+            linear_counter: 0,
+            linear_reload_value: 0,
+            linear_reload: false,
+        }
+    }
+
+    pub fn write(&mut self, register: u16, data: u8) {
+        match register {
+            // CRRR RRRR
+            0 => {
+                self.control = (data & 0x80) != 0;
+                self.length_counter.halt = self.control;
+                self.linear_reload_value = data & 0x7F;
+            }
+            1 => {}
+            2 => self.timer_period = (self.timer_period & 0x0700) | data as u16,
+            // LLLL LTTT
+            _ => {
+                self.timer_period = (self.timer_period & 0x00FF) | ((data & 0x07) as u16) << 8;
+                self.length_counter.load(data);
+                self.linear_reload = true;
+            }
+        }
+    }
+
+    // Clocked every CPU cycle, the sequencer only advances while both counters are non-zero
+    pub fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            if self.linear_counter > 0 && self.length_counter.active() {
+                self.sequence = (self.sequence + 1) & 0x1F;
+            }
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn clock_linear_counter(&mut self) {
+        if self.linear_reload {
+            self.linear_counter = self.linear_reload_value;
+        } else if self.linear_counter > 0 {
+            self.linear_counter -= 1;
+        }
+        if !self.control {
+            self.linear_reload = false;
+        }
+    }
+
+    // Halting keeps the last step on the output instead of dropping to 0
+    pub fn output(&self) -> u8 {
+        SEQUENCE[self.sequence as usize]
+    }
+}
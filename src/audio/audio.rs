@@ -0,0 +1,44 @@
+use crate::{apu::apu::ChannelOutput, constants};
+
+use super::{filter::OutputFilter, mixer::Mixer, resampler::Resampler};
+
+// Turns the per-cycle APU output into filtered samples at the output rate
+pub struct Audio {
+    mixer: Mixer,
+    resampler: Resampler,
+    filter: OutputFilter,
+    resampled: Vec<f32>,
+    samples: Vec<f32>,
+}
+
+impl Audio {
+    pub fn new(sample_rate: u32) -> Audio {
+        Audio {
+            mixer: Mixer::new(),
+            resampler: Resampler::new(constants::audio::CPU_CLOCK_RATE, sample_rate as f64),
+            filter: OutputFilter::new(sample_rate),
+            resampled: vec![],
+            samples: vec![],
+        }
+    }
+
+    // Called once per CPU cycle
+    pub fn clock(&mut self, output: ChannelOutput, expansion: Option<f32>) {
+        self.resampler.push(self.mixer.mix(output, expansion));
+    }
+
+    // Resamples and filters everything clocked since the last call,
+    // returns the samples that were added
+    pub fn end_frame(&mut self) -> &[f32] {
+        let start = self.samples.len();
+        self.resampler.read(&mut self.resampled);
+        for sample in self.resampled.drain(..) {
+            self.samples.push(self.filter.process(sample));
+        }
+        &self.samples[start..]
+    }
+
+    pub fn take_samples(&mut self) -> Vec<f32> {
+        std::mem::take(&mut self.samples)
+    }
+}
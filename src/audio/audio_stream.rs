@@ -0,0 +1,107 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rodio::{OutputStreamHandle, PlayError, Source};
+
+// Buffered audio the stream tries to keep, and the point where it starts dropping samples
+const TARGET_LATENCY: Duration = Duration::from_millis(60);
+const MAX_LATENCY: Duration = Duration::from_millis(150);
+
+struct RingBuffer {
+    samples: VecDeque<f32>,
+    // Repeated while the buffer is empty, so an underrun doesn't click
+    last_sample: f32,
+    underruns: u64,
+}
+
+// Streams samples to the audio device through a ring buffer shared with the playback thread
+pub struct AudioStream {
+    buffer: Arc<Mutex<RingBuffer>>,
+    sample_rate: u32,
+}
+
+impl AudioStream {
+    pub fn new(device: &OutputStreamHandle, sample_rate: u32) -> Result<AudioStream, PlayError> {
+        let buffer = Arc::new(Mutex::new(RingBuffer {
+            samples: VecDeque::new(),
+            last_sample: 0.0,
+            underruns: 0,
+        }));
+        device.play_raw(StreamSource {
+            buffer: Arc::clone(&buffer),
+            sample_rate,
+        })?;
+        Ok(AudioStream {
+            buffer,
+            sample_rate,
+        })
+    }
+
+    pub fn push(&mut self, samples: &[f32]) {
+        let mut buffer = self.buffer.lock().unwrap();
+        buffer.samples.extend(samples);
+
+        // Running ahead of the device, skip back to the target latency
+        if buffer.samples.len() > self.latency_samples(MAX_LATENCY) {
+            let excess = buffer.samples.len() - self.latency_samples(TARGET_LATENCY);
+            buffer.samples.drain(..excess);
+        }
+    }
+
+    pub fn latency(&self) -> Duration {
+        let buffered = self.buffer.lock().unwrap().samples.len();
+        Duration::from_secs_f64(buffered as f64 / self.sample_rate as f64)
+    }
+
+    pub fn underruns(&self) -> u64 {
+        self.buffer.lock().unwrap().underruns
+    }
+
+    fn latency_samples(&self, latency: Duration) -> usize {
+        (latency.as_secs_f64() * self.sample_rate as f64) as usize
+    }
+}
+
+struct StreamSource {
+    buffer: Arc<Mutex<RingBuffer>>,
+    sample_rate: u32,
+}
+
+impl Iterator for StreamSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let mut buffer = self.buffer.lock().unwrap();
+        match buffer.samples.pop_front() {
+            Some(sample) => {
+                buffer.last_sample = sample;
+                Some(sample)
+            }
+            None => {
+                buffer.underruns += 1;
+                Some(buffer.last_sample)
+            }
+        }
+    }
+}
+
+impl Source for StreamSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
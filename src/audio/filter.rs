@@ -0,0 +1,68 @@
+use std::f32::consts::PI;
+
+// First-order RC filter
+pub struct Filter {
+    high_pass: bool,
+    alpha: f32,
+    previous_input: f32,
+    previous_output: f32,
+}
+
+impl Filter {
+    pub fn high_pass(sample_rate: f32, cutoff: f32) -> Filter {
+        let rc = 1.0 / (2.0 * PI * cutoff);
+        let dt = 1.0 / sample_rate;
+        Filter {
+            high_pass: true,
+            alpha: rc / (rc + dt),
+            previous_input: 0.0,
+            previous_output: 0.0,
+        }
+    }
+
+    pub fn low_pass(sample_rate: f32, cutoff: f32) -> Filter {
+        let rc = 1.0 / (2.0 * PI * cutoff);
+        let dt = 1.0 / sample_rate;
+        Filter {
+            high_pass: false,
+            alpha: dt / (rc + dt),
+            previous_input: 0.0,
+            previous_output: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        let output = if self.high_pass {
+            self.alpha * (self.previous_output + input - self.previous_input)
+        } else {
+            self.previous_output + self.alpha * (input - self.previous_output)
+        };
+        self.previous_input = input;
+        self.previous_output = output;
+        output
+    }
+}
+
+// High-pass 90 Hz, high-pass 440 Hz and low-pass 14 kHz, like the NES output stage
+pub struct OutputFilter {
+    filters: [Filter; 3],
+}
+
+impl OutputFilter {
+    pub fn new(sample_rate: u32) -> OutputFilter {
+        let rate = sample_rate as f32;
+        OutputFilter {
+            filters: [
+                Filter::high_pass(rate, 90.0),
+                Filter::high_pass(rate, 440.0),
+                Filter::low_pass(rate, 14000.0),
+            ],
+        }
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        self.filters
+            .iter_mut()
+            .fold(input, |sample, filter| filter.process(sample))
+    }
+}
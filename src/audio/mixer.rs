@@ -0,0 +1,48 @@
+use crate::apu::apu::ChannelOutput;
+
+// Nonlinear DAC of the 2A03, approximated with the lookup tables from the NESdev wiki
+pub struct Mixer {
+    pulse_table: [f32; 31],
+    tnd_table: [f32; 203],
+}
+
+impl Mixer {
+    pub fn new() -> Mixer {
+        let mut pulse_table = [0.0; 31];
+        for (n, value) in pulse_table.iter_mut().enumerate().skip(1) {
+            *value = 95.52 / (8128.0 / n as f32 + 100.0);
+        }
+        let mut tnd_table = [0.0; 203];
+        for (n, value) in tnd_table.iter_mut().enumerate().skip(1) {
+            *value = 163.67 / (24329.0 / n as f32 + 100.0);
+        }
+        Mixer {
+            pulse_table,
+            tnd_table,
+        }
+    }
+
+    // Mixes the channels into a level between 0.0 and 1.0
+    pub fn mix(&self, output: ChannelOutput, expansion: Option<f32>) -> f32 {
+        let pulse = self.pulse_table[(output.pulse_1 + output.pulse_2) as usize];
+        let tnd = self.tnd_table
+            [(3 * output.triangle as usize) + (2 * output.noise as usize) + output.dmc as usize];
+        let level = pulse + tnd;
+        match expansion {
+            Some(expansion) => (level + expansion) * 0.5,
+            None => level,
+        }
+    }
+
+    // Level of each channel as if the others were silent,
+    // in the order pulse 1, pulse 2, triangle, noise and DMC
+    pub fn channel_levels(&self, output: ChannelOutput) -> [f32; 5] {
+        [
+            self.pulse_table[output.pulse_1 as usize],
+            self.pulse_table[output.pulse_2 as usize],
+            self.tnd_table[3 * output.triangle as usize],
+            self.tnd_table[2 * output.noise as usize],
+            self.tnd_table[output.dmc as usize],
+        ]
+    }
+}
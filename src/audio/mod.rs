@@ -0,0 +1,7 @@
+pub mod audio;
+pub mod audio_stream;
+mod filter;
+mod mixer;
+mod resampler;
+pub mod wav_dump;
+mod wav_writer;
@@ -0,0 +1,93 @@
+use std::f64::consts::PI;
+
+// Sub-sample positions of the step kernel
+const PHASES: usize = 64;
+// Output samples each step is spread over
+const KERNEL_WIDTH: usize = 16;
+// Fraction of the output Nyquist frequency kept by the kernel
+const CUTOFF: f64 = 0.9;
+
+// Band-limited step synthesis, in the spirit of blip_buf:
+// every change of the input level adds a windowed-sinc impulse at its exact
+// sub-sample position, integrating the result gives an alias-free step
+pub struct Resampler {
+    ratio: f64,
+    kernel: Vec<[f32; KERNEL_WIDTH]>,
+    buffer: Vec<f32>,
+    // Output sample position of the next input sample, relative to buffer[0]
+    position: f64,
+    level: f32,
+    integrator: f32,
+}
+
+impl Resampler {
+    pub fn new(clock_rate: f64, sample_rate: f64) -> Resampler {
+        Resampler {
+            ratio: sample_rate / clock_rate,
+            kernel: Resampler::create_kernel(),
+            buffer: vec![0.0; KERNEL_WIDTH * 2],
+            position: 0.0,
+            level: 0.0,
+            integrator: 0.0,
+        }
+    }
+
+    fn create_kernel() -> Vec<[f32; KERNEL_WIDTH]> {
+        let center = (KERNEL_WIDTH / 2) as f64;
+        (0..PHASES)
+            .map(|phase| {
+                let offset = phase as f64 / PHASES as f64;
+                let mut taps = [0.0; KERNEL_WIDTH];
+                for (i, tap) in taps.iter_mut().enumerate() {
+                    let x = i as f64 - center + 1.0 - offset;
+                    let sinc = if x == 0.0 {
+                        1.0
+                    } else {
+                        (PI * CUTOFF * x).sin() / (PI * CUTOFF * x)
+                    };
+                    // Blackman window over the kernel width
+                    let w = (x + center) / KERNEL_WIDTH as f64;
+                    let window = 0.42 - 0.5 * (2.0 * PI * w).cos() + 0.08 * (4.0 * PI * w).cos();
+                    *tap = (sinc * window) as f32;
+                }
+                // Every phase has to add exactly the step height
+                let sum: f32 = taps.iter().sum();
+                taps.iter_mut().for_each(|tap| *tap /= sum);
+                taps
+            })
+            .collect()
+    }
+
+    // Takes one input sample at the clock rate
+    pub fn push(&mut self, level: f32) {
+        if level != self.level {
+            self.add_delta(level - self.level);
+            self.level = level;
+        }
+        self.position += self.ratio;
+    }
+
+    fn add_delta(&mut self, delta: f32) {
+        let index = self.position as usize;
+        let phase = ((self.position - index as f64) * PHASES as f64) as usize;
+        if self.buffer.len() < index + KERNEL_WIDTH {
+            self.buffer.resize(index + KERNEL_WIDTH, 0.0);
+        }
+        for (sample, tap) in self.buffer[index..].iter_mut().zip(&self.kernel[phase]) {
+            *sample += tap * delta;
+        }
+    }
+
+    // Moves all completed output samples into out
+    pub fn read(&mut self, out: &mut Vec<f32>) {
+        let available = self.position as usize;
+        if self.buffer.len() < available + KERNEL_WIDTH {
+            self.buffer.resize(available + KERNEL_WIDTH, 0.0);
+        }
+        for delta in self.buffer.drain(..available) {
+            self.integrator += delta;
+            out.push(self.integrator);
+        }
+        self.position -= available as f64;
+    }
+}
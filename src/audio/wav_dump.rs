@@ -0,0 +1,81 @@
+use std::{io, path::Path};
+
+use crate::{apu::apu::ChannelOutput, constants};
+
+use super::{filter::OutputFilter, mixer::Mixer, resampler::Resampler, wav_writer::WavWriter};
+
+const CHANNELS: usize = 5;
+
+// Records the emulated audio into a WAV file. The mixed output goes into the first
+// channel, optionally followed by pulse 1, pulse 2, triangle, noise and DMC on their own.
+// Each channel goes through the same output filters as the mix
+pub struct WavDump {
+    writer: WavWriter,
+    mixer: Mixer,
+    resamplers: Vec<Resampler>,
+    filters: Vec<OutputFilter>,
+    channels: Vec<Vec<f32>>,
+    frame: Vec<f32>,
+}
+
+impl WavDump {
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        sample_rate: u32,
+        per_channel: bool,
+    ) -> io::Result<WavDump> {
+        let split = if per_channel { CHANNELS } else { 0 };
+        Ok(WavDump {
+            writer: WavWriter::create(path, 1 + split as u16, sample_rate)?,
+            mixer: Mixer::new(),
+            resamplers: (0..split)
+                .map(|_| Resampler::new(constants::audio::CPU_CLOCK_RATE, sample_rate as f64))
+                .collect(),
+            filters: (0..split).map(|_| OutputFilter::new(sample_rate)).collect(),
+            channels: vec![vec![]; split],
+            frame: vec![],
+        })
+    }
+
+    // Called once per CPU cycle, in step with the main audio output
+    pub fn clock(&mut self, output: ChannelOutput) {
+        if self.resamplers.is_empty() {
+            return;
+        }
+        let levels = self.mixer.channel_levels(output);
+        for (resampler, level) in self.resamplers.iter_mut().zip(levels) {
+            resampler.push(level);
+        }
+    }
+
+    // Takes the mixed samples of a frame, the channel resamplers yield the same count
+    pub fn write(&mut self, mixed: &[f32]) -> io::Result<()> {
+        for ((resampler, filter), samples) in self
+            .resamplers
+            .iter_mut()
+            .zip(&mut self.filters)
+            .zip(&mut self.channels)
+        {
+            samples.clear();
+            resampler.read(samples);
+            for sample in samples.iter_mut() {
+                *sample = filter.process(*sample);
+            }
+        }
+
+        self.frame.clear();
+        for (i, sample) in mixed.iter().enumerate() {
+            self.frame.push(*sample);
+            self.frame.extend(
+                self.channels
+                    .iter()
+                    .map(|samples| samples.get(i).copied().unwrap_or(0.0)),
+            );
+        }
+        self.writer.write(&self.frame)
+    }
+
+    pub fn finish(self) -> io::Result<()> {
+        self.writer.finish()
+    }
+}
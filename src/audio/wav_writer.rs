@@ -0,0 +1,73 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+const HEADER_SIZE: u32 = 44;
+const BITS_PER_SAMPLE: u16 = 16;
+
+// Streams interleaved 16-bit PCM samples into a RIFF/WAVE file,
+// the chunk sizes are patched in once the writer is finished
+pub struct WavWriter {
+    file: BufWriter<File>,
+    channels: u16,
+    data_size: u32,
+}
+
+impl WavWriter {
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        channels: u16,
+        sample_rate: u32,
+    ) -> io::Result<WavWriter> {
+        let mut writer = WavWriter {
+            file: BufWriter::new(File::create(path)?),
+            channels,
+            data_size: 0,
+        };
+        writer.write_header(sample_rate)?;
+        Ok(writer)
+    }
+
+    fn write_header(&mut self, sample_rate: u32) -> io::Result<()> {
+        let block_align = self.channels * BITS_PER_SAMPLE / 8;
+
+        self.file.write_all(b"RIFF")?;
+        self.file.write_all(&(HEADER_SIZE - 8).to_le_bytes())?;
+        self.file.write_all(b"WAVE")?;
+
+        self.file.write_all(b"fmt ")?;
+        self.file.write_all(&16u32.to_le_bytes())?;
+        // Uncompressed PCM
+        self.file.write_all(&1u16.to_le_bytes())?;
+        self.file.write_all(&self.channels.to_le_bytes())?;
+        self.file.write_all(&sample_rate.to_le_bytes())?;
+        self.file
+            .write_all(&(sample_rate * block_align as u32).to_le_bytes())?;
+        self.file.write_all(&block_align.to_le_bytes())?;
+        self.file.write_all(&BITS_PER_SAMPLE.to_le_bytes())?;
+
+        self.file.write_all(b"data")?;
+        self.file.write_all(&0u32.to_le_bytes())
+    }
+
+    // Samples between -1.0 and 1.0, one per channel for each frame
+    pub fn write(&mut self, samples: &[f32]) -> io::Result<()> {
+        for sample in samples {
+            let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            self.file.write_all(&value.to_le_bytes())?;
+        }
+        self.data_size += (samples.len() * 2) as u32;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.file.seek(SeekFrom::Start(4))?;
+        self.file
+            .write_all(&(HEADER_SIZE - 8 + self.data_size).to_le_bytes())?;
+        self.file.seek(SeekFrom::Start(40))?;
+        self.file.write_all(&self.data_size.to_le_bytes())?;
+        self.file.flush()
+    }
+}
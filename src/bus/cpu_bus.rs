@@ -1,11 +1,27 @@
 use std::{cell::RefCell, rc::Rc};
 
-use crate::{cartridge::cartridge::Cartridge, constants, ppu::ppu::Ppu, ram::cpu_ram::CpuRAM};
+use crate::{
+    apu::apu::Apu, cartridge::cartridge::Cartridge, constants, controller::controller::Controller,
+    ppu::ppu::Ppu, ram::cpu_ram::CpuRAM,
+};
+
+use super::{
+    dma::{Dma, DmaCycle},
+    irq_line::{IrqLine, IrqSource},
+};
 
 pub struct CpuBus {
     ram: Box<CpuRAM>,
     cartridge: Rc<RefCell<Cartridge>>,
     pub ppu: Box<Ppu>,
+    pub apu: Box<Apu>,
+    pub controllers: [Controller; 2],
+    pub irq: IrqLine,
+    // Last value seen on the data bus, undriven bits read back as this
+    open_bus: u8,
+    // The CPU is halted while DMA owns the bus
+    pub dma: Dma,
+    cycle: u64,
 }
 
 impl CpuBus {
@@ -15,38 +31,116 @@ impl CpuBus {
             ram: Box::new(CpuRAM::new()),
             ppu: Box::new(Ppu::new(Rc::clone(&cartridge))),
             cartridge, // cartridge,
+            apu: Box::new(Apu::new()),
+            controllers: [Controller::new(), Controller::new()],
+            irq: IrqLine::new(),
+            open_bus: 0x00,
+            dma: Dma::new(),
+            cycle: 0,
         }
     }
 
+    pub fn get_cartridge(&self) -> &Rc<RefCell<Cartridge>> {
+        &self.cartridge
+    }
+
     pub fn change_cartridge(&mut self, cartridge: Rc<RefCell<Cartridge>>) {
         self.cartridge = Rc::clone(&cartridge);
         self.ppu.bus.change_cartridge(cartridge)
     }
 
+    // Clocks the devices that run alongside the CPU
+    pub fn clock(&mut self) {
+        self.apu.clock();
+        self.dma
+            .set_dmc_request(self.apu.dmc_dma_address().is_some());
+        self.cartridge.borrow_mut().cpu_clock();
+
+        self.irq.set(IrqSource::FRAME_COUNTER, self.apu.frame_irq());
+        self.irq.set(IrqSource::DMC, self.apu.dmc_irq());
+        self.irq
+            .set(IrqSource::MAPPER, self.cartridge.borrow().irq_pending());
+        self.cycle += 1;
+    }
+
+    // Runs one CPU cycle of DMA in place of the CPU
+    pub fn dma_cycle(&mut self) {
+        let get = (self.cycle & 0x01) == 0;
+        match self.dma.next_cycle(get) {
+            DmaCycle::DmcRead => {
+                if let Some(addr) = self.apu.dmc_dma_address() {
+                    let data = self.read(addr);
+                    self.apu.dmc_dma_complete(data);
+                }
+            }
+            DmaCycle::OamRead(addr) => self.dma.oam_data = Some(self.read(addr)),
+            DmaCycle::OamWrite(data) => self.ppu.cpu_write(constants::ppu::OAMDATA_ADDR, data),
+            DmaCycle::Halt | DmaCycle::Idle => {}
+        }
+    }
+
+    // Takes a pending NMI raised by the PPU
+    pub fn poll_nmi(&mut self) -> bool {
+        let nmi = self.ppu.nmi;
+        self.ppu.nmi = false;
+        nmi
+    }
+
     pub fn write(&mut self, addr: u16, data: u8) {
+        self.open_bus = data;
         if self.cartridge.borrow_mut().cpu_write(addr, data) {
             //
         } else if addr >= constants::cpu::RAM_ADDR_MIN && addr <= constants::cpu::RAM_ADDR_MAX {
             self.ram.write(addr & 0x07FF, data);
         } else if addr >= constants::ppu::RAM_ADDR_MIN && addr <= constants::ppu::RAM_ADDR_MAX {
-            // Might cause runtime error due to double mut borrow
-            // Change to PPU Write instead of PPU Bus => PPU Bus only accessible by PPU
-            self.ppu.bus.write(addr & 0x0007, data);
+            self.ppu.cpu_write(addr & 0x0007, data);
+        } else if addr == constants::ppu::OAM_DMA_ADDR {
+            self.dma.start_oam(data);
+        } else if addr == constants::controller::PORT_1_ADDR {
+            // Both controllers share the strobe line
+            for controller in self.controllers.iter_mut() {
+                controller.write(data);
+            }
+        } else if (constants::apu::REGISTER_ADDR_MIN..=constants::apu::REGISTER_ADDR_MAX)
+            .contains(&addr)
+            || addr == constants::apu::STATUS_ADDR
+            || addr == constants::apu::FRAME_COUNTER_ADDR
+        {
+            self.apu.cpu_write(addr, data);
         }
     }
 
     pub fn read(&mut self, addr: u16) -> u8 {
+        self.read_mut(addr, false)
+    }
+
+    // Reads without triggering side effects, e.g. for the debugger
+    pub fn peek(&mut self, addr: u16) -> u8 {
+        self.read_mut(addr, true)
+    }
+
+    fn read_mut(&mut self, addr: u16, readonly: bool) -> u8 {
         let mut data = 0;
         if self.cartridge.borrow().cpu_read(addr, &mut data) {
-            return data;
+            //
         } else if addr >= constants::cpu::RAM_ADDR_MIN && addr <= constants::cpu::RAM_ADDR_MAX {
-            return self.ram.read(addr & 0x07FF);
+            data = self.ram.read(addr & 0x07FF);
         } else if addr >= constants::ppu::RAM_ADDR_MIN && addr <= constants::ppu::RAM_ADDR_MAX {
-            // Might cause runtime error due to double mut borrow
-            // Change to PPU Read instead of PPU Bus => PPU Bus only accessible by PPU
-            return self.ppu.bus.read(addr & 0x0007, true);
+            data = self.ppu.cpu_read(addr & 0x0007, readonly);
+        } else if addr == constants::apu::STATUS_ADDR {
+            // Bit 5 is not driven by the APU
+            data = (self.open_bus & 0x20) | self.apu.read_status(readonly);
+        } else if addr == constants::controller::PORT_1_ADDR
+            || addr == constants::controller::PORT_2_ADDR
+        {
+            // Only the low bits are driven, the rest is open bus
+            let port = (addr - constants::controller::PORT_1_ADDR) as usize;
+            data = (self.open_bus & 0xE0) | self.controllers[port].read(readonly);
         }
 
-        0x00
+        if !readonly {
+            self.open_bus = data;
+        }
+        data
     }
 }
@@ -0,0 +1,91 @@
+// Halt and dummy cycles the DMC waits for before its read
+const DMC_DMA_DELAY: u8 = 2;
+
+// State of the OAM and DMC DMA units, both halt the CPU and share its bus.
+// Reads only happen on get cycles and writes on put cycles, which alternate
+pub struct Dma {
+    // Page being copied to OAM and the next byte of it
+    oam_page: Option<u8>,
+    oam_offset: u16,
+    // Byte read on a get cycle, written to OAM on the next put cycle
+    pub oam_data: Option<u8>,
+
+    dmc_pending: bool,
+    dmc_delay: u8,
+
+    // The CPU spends one cycle halting before any transfer
+    halted: bool,
+}
+
+pub enum DmaCycle {
+    Halt,
+    DmcRead,
+    OamRead(u16),
+    OamWrite(u8),
+    // Dummy or alignment cycle
+    Idle,
+}
+
+impl Dma {
+    pub fn new() -> Dma {
+        Dma {
+            oam_page: None,
+            oam_offset: 0,
+            oam_data: None,
+            dmc_pending: false,
+            dmc_delay: 0,
+            halted: false,
+        }
+    }
+
+    // Written to $4014, copies $XX00-$XXFF through $2004
+    pub fn start_oam(&mut self, page: u8) {
+        self.oam_page = Some(page);
+        self.oam_offset = 0;
+        self.oam_data = None;
+    }
+
+    // The DMC holds its request until the sample buffer is filled, or the channel disabled
+    pub fn set_dmc_request(&mut self, requested: bool) {
+        if requested && !self.dmc_pending {
+            self.dmc_delay = DMC_DMA_DELAY;
+        }
+        self.dmc_pending = requested;
+    }
+
+    pub fn halted(&self) -> bool {
+        self.halted
+    }
+
+    pub fn active(&self) -> bool {
+        self.oam_page.is_some() || self.dmc_pending
+    }
+
+    // Decides what the bus does this cycle. The DMC takes precedence on get cycles,
+    // when it runs during an OAM transfer its halt and dummy cycles overlap with it
+    pub fn next_cycle(&mut self, get: bool) -> DmaCycle {
+        let cycle = if !self.halted {
+            self.halted = true;
+            DmaCycle::Halt
+        } else if get && self.dmc_pending && self.dmc_delay == 0 {
+            self.dmc_pending = false;
+            DmaCycle::DmcRead
+        } else if let (true, Some(page), None) = (get, self.oam_page, self.oam_data) {
+            DmaCycle::OamRead(((page as u16) << 8) | self.oam_offset)
+        } else if let (false, Some(data)) = (get, self.oam_data.take()) {
+            self.oam_offset += 1;
+            if self.oam_offset == 256 {
+                self.oam_page = None;
+            }
+            DmaCycle::OamWrite(data)
+        } else {
+            DmaCycle::Idle
+        };
+
+        self.dmc_delay = self.dmc_delay.saturating_sub(1);
+        if !self.active() {
+            self.halted = false;
+        }
+        cycle
+    }
+}
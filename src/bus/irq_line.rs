@@ -0,0 +1,46 @@
+use bitflags::bitflags;
+
+bitflags! {
+
+    // Devices that can hold the IRQ line
+    #[derive(Clone, Copy, PartialEq)]
+    pub struct IrqSource: u8 {
+        const FRAME_COUNTER = (1 << 0);
+        const DMC = (1 << 1);
+        const MAPPER = (1 << 2);
+    }
+
+}
+
+// Level-triggered and shared, the line stays asserted as long as any source holds it
+pub struct IrqLine {
+    sources: IrqSource,
+}
+
+impl IrqLine {
+    pub fn new() -> IrqLine {
+        IrqLine {
+            sources: IrqSource::empty(),
+        }
+    }
+
+    pub fn assert(&mut self, source: IrqSource) {
+        self.sources |= source;
+    }
+
+    pub fn acknowledge(&mut self, source: IrqSource) {
+        self.sources &= !source;
+    }
+
+    pub fn set(&mut self, source: IrqSource, asserted: bool) {
+        if asserted {
+            self.assert(source);
+        } else {
+            self.acknowledge(source);
+        }
+    }
+
+    pub fn asserted(&self) -> bool {
+        !self.sources.is_empty()
+    }
+}
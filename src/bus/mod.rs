@@ -1,2 +1,4 @@
 pub mod cpu_bus;
+pub mod dma;
+pub mod irq_line;
 pub mod ppu_bus;
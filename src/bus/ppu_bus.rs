@@ -1,6 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
-use crate::{cartridge::cartridge::Cartridge, ram::ppu_ram::PpuRAM};
+use crate::{cartridge::cartridge::Cartridge, constants, ram::ppu_ram::PpuRAM};
 
 pub struct PpuBus {
     pub ram: Box<PpuRAM>,
@@ -20,17 +20,31 @@ impl PpuBus {
     }
 
     pub fn read(&self, addr: u16, readonly: bool) -> u8 {
+        if !readonly {
+            self.notify_address(addr);
+        }
         let mut data = 0;
         if self.cartridge.borrow().ppu_read(addr, &mut data) {
-            //
+            return data;
         }
-        return self.ram.read(addr, readonly);
+        return self
+            .ram
+            .read(addr, self.cartridge.borrow().mirror(), readonly);
     }
 
     pub fn write(&mut self, addr: u16, data: u8) {
+        self.notify_address(addr);
         if self.cartridge.borrow_mut().ppu_write(addr, data) {
-            //
+            return;
+        }
+        let mirror = self.cartridge.borrow().mirror();
+        self.ram.write(addr, mirror, data);
+    }
+
+    // Palette accesses stay inside the PPU and never reach the cartridge
+    fn notify_address(&self, addr: u16) {
+        if addr < constants::ppu::PALETTE_ADDR {
+            self.cartridge.borrow_mut().ppu_address(addr);
         }
-        self.ram.write(addr, data);
     }
 }
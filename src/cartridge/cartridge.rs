@@ -1,145 +1,254 @@
-use bincode::deserialize;
-use serde::Deserialize;
-use std::fs;
-
-use super::mapper::{Mapper, MapperId};
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use super::{
+    header::{RomHeader, CHR_BANK_SIZE, HEADER_SIZE, TRAINER_SIZE},
+    mapper::{Mapper, MapperId, Mapping},
+    mirror::Mirror,
+    rom_error::RomError,
+};
 
 pub struct Cartridge {
     vprg_memory: Vec<u8>,
     vchr_memory: Vec<u8>,
 
-    #[allow(unused)]
-    mapper_id: u8,
-    #[allow(unused)]
-    prg_banks: u8,
-    #[allow(unused)]
-    chr_banks: u8,
+    header: RomHeader,
     mapper: Box<dyn Mapper>,
+
+    // Additional nametable RAM of four-screen boards
+    vram: Vec<u8>,
+
+    prg_ram: Vec<u8>,
+    // Battery-backed boards keep PRG-RAM in a .sav file next to the ROM
+    save_path: Option<PathBuf>,
+    prg_ram_dirty: bool,
 }
 
 impl Cartridge {
     pub fn new() -> Cartridge {
+        // Empty NROM-128 board
+        let header = RomHeader::from_bytes([
+            b'N', b'E', b'S', 0x1A, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00,
+        ]);
         Cartridge {
-            vprg_memory: vec![0; 16384],
-            vchr_memory: vec![0; 8192],
-            mapper_id: 0,
-            prg_banks: 1,
-            chr_banks: 1,
-            mapper: MapperId(0).into(),
+            vprg_memory: vec![0; header.prg_rom_size],
+            vchr_memory: vec![0; header.chr_rom_size],
+            mapper: MapperId(header.mapper_id)
+                .create(&header)
+                .expect("NROM is always supported"),
+            vram: vec![],
+            prg_ram: vec![0; header.work_ram_size()],
+            save_path: None,
+            prg_ram_dirty: false,
+            header,
         }
     }
 
-    pub fn from_file(filename: &str) -> Cartridge {
-        let content = fs::read(filename).expect("Failed reading the cartridge file");
-        let mut header_bytes: [u8; 16] = [0x00; 16];
-
-        for i in 0..16 {
-            header_bytes[i] = content[i]
+    pub fn from_file(filename: &str) -> Result<Cartridge, RomError> {
+        let content = fs::read(filename)?;
+        let header = RomHeader::parse(&content)?;
+        // Every board needs PRG-ROM, at least for the reset vector
+        if header.prg_rom_size == 0 {
+            return Err(RomError::EmptyPrg);
         }
 
-        let header = INesHeader::new(header_bytes);
-        let mapper_id = header.mapper_id();
-        let prg_banks = header.prg_rom_chunks;
-        let chr_banks = header.chr_rom_chunks;
+        let mapper = MapperId(header.mapper_id).create(&header)?;
 
-        let mut offset: usize = 0;
-        offset += 16; // Header Size
+        let mut offset: usize = HEADER_SIZE;
 
-        if header.has_trainer() {
-            offset += 512; // Trainer Size
+        if header.trainer {
+            offset += TRAINER_SIZE;
         }
 
-        let filetype = 1;
-        let mut vprg_memory = vec![];
-        let mut vchr_memory = vec![];
+        let vprg_memory =
+            Cartridge::read_section(&content, offset, header.prg_rom_size).map_err(|found| {
+                RomError::TruncatedPrg {
+                    expected: header.prg_rom_size,
+                    found,
+                }
+            })?;
+        offset += vprg_memory.len();
+
+        let mut vchr_memory = Cartridge::read_section(&content, offset, header.chr_rom_size)
+            .map_err(|found| RomError::TruncatedChr {
+                expected: header.chr_rom_size,
+                found,
+            })?;
+        offset += vchr_memory.len();
+
+        // Boards without CHR-ROM carry CHR-RAM instead
+        if vchr_memory.is_empty() {
+            vchr_memory = vec![0; (header.chr_ram_size + header.chr_nvram_size).max(CHR_BANK_SIZE)];
+        }
 
-        if filetype == 1 {
-            vprg_memory = vec![0; prg_banks as usize * 16384];
-            for i in 0..vprg_memory.len() {
-                vprg_memory[i] = content[offset + i];
-            }
-            offset += vprg_memory.len(); // vPRG Memory Size
+        // Small amounts of padding or title data are common in dumps, anything
+        // bigger than a bank means the header does not describe the ROM
+        let trailing = content.len() - offset;
+        if trailing >= CHR_BANK_SIZE && header.misc_roms == 0 {
+            return Err(RomError::TrailingData(trailing));
+        }
+        if trailing > 0 {
+            println!("Warning: Ignoring {} bytes after CHR-ROM", trailing);
+        }
 
-            vchr_memory = vec![0; chr_banks as usize * 8192];
+        Ok(Cartridge {
+            vprg_memory,
+            vchr_memory,
+            mapper,
+            vram: vec![
+                0;
+                if header.mirror == Mirror::FourScreen {
+                    2048
+                } else {
+                    0
+                }
+            ],
+            prg_ram: vec![0; header.work_ram_size()],
+            save_path: if header.battery {
+                Some(Path::new(filename).with_extension("sav"))
+            } else {
+                None
+            },
+            prg_ram_dirty: false,
+            header,
+        })
+    }
 
-            for i in 0..vchr_memory.len() {
-                vchr_memory[i] = content[offset + i];
-            }
-            offset += vchr_memory.len(); // vPRG Memory Size
+    // Copies a ROM section, or returns how many bytes were actually available
+    fn read_section(content: &[u8], offset: usize, size: usize) -> Result<Vec<u8>, usize> {
+        let available = content.len().saturating_sub(offset);
+        if available < size {
+            return Err(available);
         }
+        Ok(content[offset..offset + size].to_vec())
+    }
 
-        assert_eq!(offset, content.len());
+    pub fn header(&self) -> &RomHeader {
+        &self.header
+    }
 
-        Cartridge {
-            vprg_memory,
-            vchr_memory,
-            mapper_id,
-            prg_banks,
-            chr_banks,
-            mapper: MapperId(mapper_id).into(),
+    pub fn mirror(&self) -> Mirror {
+        match self.mapper.mirror() {
+            Mirror::Hardware => self.header.mirror,
+            mirror => mirror,
         }
     }
 
     pub fn cpu_read(&self, addr: u16, data: &mut u8) -> bool {
-        let mut mapped_addr: u32 = 0;
-        if self.mapper.cpu_read(addr, &mut mapped_addr) {
-            *data = self.vprg_memory[mapped_addr as usize];
-            return true;
+        match self.mapper.cpu_read(addr) {
+            // Banks past a PRG-ROM that isn't a whole number of them mirror it
+            Mapping::Memory(index) => *data = self.vprg_memory[index % self.vprg_memory.len()],
+            Mapping::Ram(index) => *data = self.prg_ram[index % self.prg_ram.len()],
+            Mapping::Data(value) => *data = value,
+            Mapping::Handled => {}
+            Mapping::Unmapped => return false,
         }
-        false
+        true
     }
-    pub fn cpu_write(&mut self, addr: u16, data: u8) -> bool {
-        let mut mapped_addr: u32 = 0;
-        if self.mapper.cpu_write(addr, &mut mapped_addr) {
-            self.vprg_memory[mapped_addr as usize] = data;
-            return true;
+
+    pub fn cpu_write(&mut self, addr: u16, mut data: u8) -> bool {
+        if addr >= 0x8000 && self.mapper.bus_conflicts() {
+            let mut rom_data = 0x00;
+            self.cpu_read(addr, &mut rom_data);
+            data &= rom_data;
+        }
+
+        match self.mapper.cpu_write(addr, data) {
+            Mapping::Memory(index) => self.vprg_memory[index] = data,
+            Mapping::Ram(index) => {
+                let len = self.prg_ram.len();
+                self.prg_ram[index % len] = data;
+                self.prg_ram_dirty = true;
+            }
+            Mapping::Unmapped => return false,
+            _ => {}
         }
-        false
+        true
+    }
+
+    pub fn cpu_clock(&mut self) {
+        self.mapper.cpu_clock();
+    }
+
+    pub fn irq_pending(&self) -> bool {
+        self.mapper.irq_pending()
+    }
+
+    pub fn audio_output(&self) -> Option<f32> {
+        self.mapper.audio_output()
+    }
+
+    pub fn ppu_address(&mut self, addr: u16) {
+        self.mapper.ppu_address(addr);
     }
 
     pub fn ppu_read(&self, addr: u16, data: &mut u8) -> bool {
-        let mut mapped_addr: u32 = 0;
-        if self.mapper.ppu_read(addr, &mut mapped_addr) {
-            *data = self.vchr_memory[mapped_addr as usize];
+        if let Some(index) = self.vram_index(addr) {
+            *data = self.vram[index];
             return true;
         }
-        false
+        match self.mapper.ppu_read(addr) {
+            Mapping::Memory(index) | Mapping::Ram(index) => *data = self.vchr_memory[index],
+            Mapping::Data(value) => *data = value,
+            Mapping::Handled => {}
+            Mapping::Unmapped => return false,
+        }
+        true
     }
 
     pub fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
-        let mut mapped_addr: u32 = 0;
-        if self.mapper.ppu_write(addr, &mut mapped_addr) {
-            self.vchr_memory[mapped_addr as usize] = data;
+        if let Some(index) = self.vram_index(addr) {
+            self.vram[index] = data;
             return true;
         }
-        false
+        match self.mapper.ppu_write(addr, data) {
+            Mapping::Memory(index) | Mapping::Ram(index) => self.vchr_memory[index] = data,
+            Mapping::Unmapped => return false,
+            _ => {}
+        }
+        true
     }
-}
 
-#[derive(Deserialize, Debug)]
-#[allow(unused)]
-struct INesHeader {
-    name: [char; 4],
-    prg_rom_chunks: u8,
-    chr_rom_chunks: u8,
-    mapper1: u8,
-    mapper2: u8,
-    prg_ram_size: u8,
-    tv_system1: u8,
-    ty_system2: u8,
-    _unused: [u8; 5],
-}
-
-impl INesHeader {
-    pub fn new(data: [u8; 16]) -> INesHeader {
-        deserialize(&data).unwrap()
+    // Restores battery-backed PRG-RAM, a missing file just means a fresh save
+    pub fn load_save(&mut self) -> io::Result<()> {
+        let Some(path) = &self.save_path else {
+            return Ok(());
+        };
+        match fs::read(path) {
+            Ok(data) => {
+                let size = data.len().min(self.prg_ram.len());
+                self.prg_ram[..size].copy_from_slice(&data[..size]);
+                Ok(())
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
     }
 
-    pub fn has_trainer(&self) -> bool {
-        return (self.mapper1 & 0x08) != 0;
+    // Writes battery-backed PRG-RAM back to disk if it changed since the last flush
+    pub fn flush_save(&mut self) -> io::Result<()> {
+        if let Some(path) = &self.save_path {
+            if self.prg_ram_dirty {
+                fs::write(path, &self.prg_ram)?;
+                self.prg_ram_dirty = false;
+            }
+        }
+        Ok(())
     }
 
-    pub fn mapper_id(&self) -> u8 {
-        ((self.mapper2 >> 4) << 4) | (self.mapper1 >> 4)
+    // Four-screen boards provide nametables 2 and 3, the PPU's CIRAM holds 0 and 1
+    fn vram_index(&self, addr: u16) -> Option<usize> {
+        if self.mirror() != Mirror::FourScreen || !(0x2000..=0x3EFF).contains(&addr) {
+            return None;
+        }
+        let addr = (addr & 0x0FFF) as usize;
+        if addr < 0x0800 {
+            return None;
+        }
+        Some(addr - 0x0800)
     }
 }
@@ -0,0 +1,362 @@
+use bincode::deserialize;
+use serde::Deserialize;
+
+use super::{mirror::Mirror, rom_error::RomError};
+
+pub const HEADER_SIZE: usize = 16;
+pub const HEADER_MAGIC: [u8; 4] = [b'N', b'E', b'S', 0x1A];
+pub const TRAINER_SIZE: usize = 512;
+pub const PRG_BANK_SIZE: usize = 16384;
+pub const CHR_BANK_SIZE: usize = 8192;
+pub const PRG_RAM_SIZE: usize = 8192;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HeaderFormat {
+    // Pre-iNES 1.0 dumps, often with garbage in bytes 7-15
+    Archaic,
+    INes,
+    Nes20,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Timing {
+    Ntsc,
+    Pal,
+    MultiRegion,
+    Dendy,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ConsoleType {
+    Nes,
+    VsSystem { ppu: u8, hardware: u8 },
+    Playchoice10,
+    Extended(u8),
+}
+
+// Parsed contents of an iNES or NES 2.0 header, all sizes in bytes
+#[derive(Clone, Debug)]
+pub struct RomHeader {
+    pub format: HeaderFormat,
+    pub mapper_id: u16,
+    pub submapper_id: u8,
+    pub prg_rom_size: usize,
+    pub chr_rom_size: usize,
+    pub prg_ram_size: usize,
+    pub prg_nvram_size: usize,
+    pub chr_ram_size: usize,
+    pub chr_nvram_size: usize,
+    pub mirror: Mirror,
+    pub battery: bool,
+    pub trainer: bool,
+    pub timing: Timing,
+    pub console_type: ConsoleType,
+    pub misc_roms: u8,
+    pub expansion_device: u8,
+}
+
+impl RomHeader {
+    pub fn parse(content: &[u8]) -> Result<RomHeader, RomError> {
+        if content.len() < HEADER_SIZE || content[0..4] != HEADER_MAGIC {
+            return Err(RomError::BadMagic);
+        }
+
+        let mut header_bytes: [u8; HEADER_SIZE] = [0x00; HEADER_SIZE];
+        header_bytes.copy_from_slice(&content[0..HEADER_SIZE]);
+        Ok(RomHeader::from_bytes(header_bytes))
+    }
+
+    pub fn prg_banks(&self) -> usize {
+        self.prg_rom_size / PRG_BANK_SIZE
+    }
+
+    pub fn chr_banks(&self) -> usize {
+        self.chr_rom_size / CHR_BANK_SIZE
+    }
+
+    // Volatile and battery-backed work RAM, boards that declare none get 8 KiB
+    pub fn work_ram_size(&self) -> usize {
+        let size = self.prg_ram_size + self.prg_nvram_size;
+        if size == 0 {
+            return PRG_RAM_SIZE;
+        }
+        size
+    }
+
+    pub fn from_bytes(data: [u8; HEADER_SIZE]) -> RomHeader {
+        let raw = INesHeader::new(data);
+        match raw.format() {
+            HeaderFormat::Nes20 => raw.parse_nes20(),
+            format => raw.parse_ines(format),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(unused)]
+struct INesHeader {
+    name: [char; 4],
+    prg_rom_chunks: u8,
+    chr_rom_chunks: u8,
+    mapper1: u8,
+    mapper2: u8,
+    // iNES: PRG-RAM size, NES 2.0: mapper MSB and submapper
+    flags8: u8,
+    // iNES: TV system, NES 2.0: PRG/CHR-ROM size MSB
+    flags9: u8,
+    // NES 2.0: PRG-RAM and PRG-NVRAM shift counts
+    flags10: u8,
+    // NES 2.0: CHR-RAM and CHR-NVRAM shift counts
+    flags11: u8,
+    // NES 2.0: CPU/PPU timing
+    flags12: u8,
+    // NES 2.0: Vs. System or extended console type
+    flags13: u8,
+    // NES 2.0: miscellaneous ROMs
+    flags14: u8,
+    // NES 2.0: default expansion device
+    flags15: u8,
+}
+
+impl INesHeader {
+    pub fn new(data: [u8; HEADER_SIZE]) -> INesHeader {
+        deserialize(&data).unwrap()
+    }
+
+    fn format(&self) -> HeaderFormat {
+        match self.mapper2 & 0x0C {
+            0x08 => HeaderFormat::Nes20,
+            0x00 if self.flags12 == 0
+                && self.flags13 == 0
+                && self.flags14 == 0
+                && self.flags15 == 0 =>
+            {
+                HeaderFormat::INes
+            }
+            _ => HeaderFormat::Archaic,
+        }
+    }
+
+    // Bit 3 is the four-screen flag, see mirror()
+    fn has_trainer(&self) -> bool {
+        (self.mapper1 & 0x04) != 0
+    }
+
+    fn has_battery(&self) -> bool {
+        (self.mapper1 & 0x02) != 0
+    }
+
+    fn mirror(&self) -> Mirror {
+        if (self.mapper1 & 0x08) != 0 {
+            Mirror::FourScreen
+        } else if (self.mapper1 & 0x01) != 0 {
+            Mirror::Vertical
+        } else {
+            Mirror::Horizontal
+        }
+    }
+
+    fn console_type(&self) -> ConsoleType {
+        match self.mapper2 & 0x03 {
+            0x00 => ConsoleType::Nes,
+            0x01 => ConsoleType::VsSystem {
+                ppu: self.flags13 & 0x0F,
+                hardware: self.flags13 >> 4,
+            },
+            0x02 => ConsoleType::Playchoice10,
+            _ => ConsoleType::Extended(self.flags13 & 0x0F),
+        }
+    }
+
+    fn parse_ines(&self, format: HeaderFormat) -> RomHeader {
+        let mut mapper_id = (self.mapper1 >> 4) as u16;
+        // Archaic headers tend to have a signature in the upper bytes
+        if format == HeaderFormat::INes {
+            mapper_id |= (self.mapper2 & 0xF0) as u16;
+        }
+
+        let chr_rom_size = self.chr_rom_chunks as usize * CHR_BANK_SIZE;
+        let prg_ram_size = (self.flags8.max(1) as usize) * 8192;
+        let battery = self.has_battery();
+
+        RomHeader {
+            format,
+            mapper_id,
+            submapper_id: 0,
+            prg_rom_size: self.prg_rom_chunks as usize * PRG_BANK_SIZE,
+            chr_rom_size,
+            prg_ram_size: if battery { 0 } else { prg_ram_size },
+            prg_nvram_size: if battery { prg_ram_size } else { 0 },
+            chr_ram_size: if chr_rom_size == 0 { CHR_BANK_SIZE } else { 0 },
+            chr_nvram_size: 0,
+            mirror: self.mirror(),
+            battery,
+            trainer: self.has_trainer(),
+            timing: if format == HeaderFormat::INes && (self.flags9 & 0x01) != 0 {
+                Timing::Pal
+            } else {
+                Timing::Ntsc
+            },
+            console_type: match self.mapper2 & 0x03 {
+                0x01 if format == HeaderFormat::INes => ConsoleType::VsSystem {
+                    ppu: 0,
+                    hardware: 0,
+                },
+                0x02 if format == HeaderFormat::INes => ConsoleType::Playchoice10,
+                _ => ConsoleType::Nes,
+            },
+            misc_roms: 0,
+            expansion_device: 0,
+        }
+    }
+
+    fn parse_nes20(&self) -> RomHeader {
+        let mapper_id = ((self.flags8 & 0x0F) as u16) << 8
+            | (self.mapper2 & 0xF0) as u16
+            | (self.mapper1 >> 4) as u16;
+
+        RomHeader {
+            format: HeaderFormat::Nes20,
+            mapper_id,
+            submapper_id: self.flags8 >> 4,
+            prg_rom_size: INesHeader::rom_size(
+                self.prg_rom_chunks,
+                self.flags9 & 0x0F,
+                PRG_BANK_SIZE,
+            ),
+            chr_rom_size: INesHeader::rom_size(
+                self.chr_rom_chunks,
+                self.flags9 >> 4,
+                CHR_BANK_SIZE,
+            ),
+            prg_ram_size: INesHeader::ram_size(self.flags10 & 0x0F),
+            prg_nvram_size: INesHeader::ram_size(self.flags10 >> 4),
+            chr_ram_size: INesHeader::ram_size(self.flags11 & 0x0F),
+            chr_nvram_size: INesHeader::ram_size(self.flags11 >> 4),
+            mirror: self.mirror(),
+            battery: self.has_battery(),
+            trainer: self.has_trainer(),
+            timing: match self.flags12 & 0x03 {
+                0x00 => Timing::Ntsc,
+                0x01 => Timing::Pal,
+                0x02 => Timing::MultiRegion,
+                _ => Timing::Dendy,
+            },
+            console_type: self.console_type(),
+            misc_roms: self.flags14 & 0x03,
+            expansion_device: self.flags15 & 0x3F,
+        }
+    }
+
+    // Sizes with an MSB nibble of 0xF are given as 2^E * (MM * 2 + 1)
+    fn rom_size(lsb: u8, msb: u8, bank_size: usize) -> usize {
+        if msb == 0x0F {
+            let exponent = (lsb >> 2) as u32;
+            let multiplier = ((lsb & 0x03) * 2 + 1) as usize;
+            return 2usize.saturating_pow(exponent).saturating_mul(multiplier);
+        }
+        (((msb as usize) << 8) | lsb as usize) * bank_size
+    }
+
+    // RAM sizes are given as a shift count of 64 bytes, 0 means none
+    fn ram_size(shift: u8) -> usize {
+        if shift == 0 {
+            return 0;
+        }
+        64 << shift
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(bytes: [u8; 12]) -> Vec<u8> {
+        let mut content = HEADER_MAGIC.to_vec();
+        content.extend_from_slice(&bytes);
+        content
+    }
+
+    #[test]
+    fn rejects_missing_magic() {
+        assert!(matches!(
+            RomHeader::parse(b"NES\x00"),
+            Err(RomError::BadMagic)
+        ));
+        assert!(matches!(
+            RomHeader::parse(&[0x00; HEADER_SIZE]),
+            Err(RomError::BadMagic)
+        ));
+    }
+
+    #[test]
+    fn parses_ines() {
+        let rom = RomHeader::parse(&header([2, 1, 0x13, 0x40, 0, 0, 0, 0, 0, 0, 0, 0])).unwrap();
+        assert_eq!(rom.format, HeaderFormat::INes);
+        assert_eq!(rom.mapper_id, 0x41);
+        assert_eq!(rom.prg_rom_size, 2 * PRG_BANK_SIZE);
+        assert_eq!(rom.chr_rom_size, CHR_BANK_SIZE);
+        assert_eq!(rom.mirror, Mirror::Vertical);
+        assert!(rom.battery);
+        assert!(!rom.trainer);
+        assert_eq!(rom.prg_nvram_size, PRG_RAM_SIZE);
+    }
+
+    #[test]
+    fn trainer_and_four_screen_are_separate_bits() {
+        let rom = RomHeader::parse(&header([1, 1, 0x04, 0, 0, 0, 0, 0, 0, 0, 0, 0])).unwrap();
+        assert!(rom.trainer);
+        assert_eq!(rom.mirror, Mirror::Horizontal);
+
+        let rom = RomHeader::parse(&header([1, 1, 0x08, 0, 0, 0, 0, 0, 0, 0, 0, 0])).unwrap();
+        assert!(!rom.trainer);
+        assert_eq!(rom.mirror, Mirror::FourScreen);
+    }
+
+    #[test]
+    fn archaic_header_ignores_upper_mapper_nibble() {
+        let rom = RomHeader::parse(&header([
+            1, 1, 0x10, 0x40, 0, 0, 0, 0, b'D', b'i', b's', b'k',
+        ]))
+        .unwrap();
+        assert_eq!(rom.format, HeaderFormat::Archaic);
+        assert_eq!(rom.mapper_id, 1);
+    }
+
+    #[test]
+    fn parses_nes20() {
+        let rom = RomHeader::parse(&header([
+            0x02, 0x10, 0x40, 0x08, 0x31, 0x00, 0x70, 0x07, 0x01, 0, 0, 0,
+        ]))
+        .unwrap();
+        assert_eq!(rom.format, HeaderFormat::Nes20);
+        assert_eq!(rom.mapper_id, 0x104);
+        assert_eq!(rom.submapper_id, 3);
+        assert_eq!(rom.prg_rom_size, 2 * PRG_BANK_SIZE);
+        assert_eq!(rom.chr_rom_size, 16 * CHR_BANK_SIZE);
+        assert_eq!(rom.prg_ram_size, 0);
+        assert_eq!(rom.prg_nvram_size, 8192);
+        assert_eq!(rom.chr_ram_size, 8192);
+        assert_eq!(rom.timing, Timing::Pal);
+    }
+
+    #[test]
+    fn rom_size_exponent_form() {
+        // 2^13 * 1 and 2^10 * 3
+        assert_eq!(INesHeader::rom_size(13 << 2, 0x0F, PRG_BANK_SIZE), 8192);
+        assert_eq!(
+            INesHeader::rom_size((10 << 2) | 0x01, 0x0F, PRG_BANK_SIZE),
+            3072
+        );
+        assert_eq!(
+            INesHeader::rom_size(0x02, 0x01, PRG_BANK_SIZE),
+            0x102 * PRG_BANK_SIZE
+        );
+    }
+
+    #[test]
+    fn ram_size_shift_counts() {
+        assert_eq!(INesHeader::ram_size(0), 0);
+        assert_eq!(INesHeader::ram_size(7), 8192);
+    }
+}
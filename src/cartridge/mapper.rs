@@ -1,76 +1,178 @@
+use super::{
+    header::RomHeader, mapper_001::Mapper001, mapper_002::Mapper002, mapper_003::Mapper003,
+    mapper_004::Mapper004, mapper_007::Mapper007, mapper_011::Mapper011, mapper_066::Mapper066,
+    mirror::Mirror, rom_error::RomError,
+};
+
 macro_rules! mapper {
-    ($mapper:ident) => {
-        Box::new($mapper::new())
+    ($mapper:ident, $header:expr) => {
+        Box::new($mapper::new($header))
     };
 }
 
-pub struct MapperId(pub u8);
-impl From<MapperId> for Box<dyn Mapper> {
-    fn from(mapper_id: MapperId) -> Box<dyn Mapper> {
-        let MapperId(id) = mapper_id;
+// Result of decoding a CPU or PPU access
+pub enum Mapping {
+    // Offset into the cartridge PRG or CHR memory
+    Memory(usize),
+    // Offset into the cartridge PRG-RAM
+    Ram(usize),
+    // Value supplied by the mapper itself, e.g. on-board RAM
+    Data(u8),
+    // Write consumed by the mapper, e.g. a bank register
+    Handled,
+    // Not decoded by the cartridge, the access goes to the rest of the bus
+    Unmapped,
+}
+
+pub struct MapperId(pub u16);
+impl MapperId {
+    pub fn create(&self, header: &RomHeader) -> Result<Box<dyn Mapper>, RomError> {
+        let MapperId(id) = *self;
 
-        let mapper = match id {
-            0 => mapper!(Mapper000),
-            _ => mapper!(Mapper000),
+        let mapper: Box<dyn Mapper> = match id {
+            0 => mapper!(Mapper000, header),
+            1 => mapper!(Mapper001, header),
+            2 => mapper!(Mapper002, header),
+            3 => mapper!(Mapper003, header),
+            4 => mapper!(Mapper004, header),
+            7 => mapper!(Mapper007, header),
+            11 => mapper!(Mapper011, header),
+            66 => mapper!(Mapper066, header),
+            _ => return Err(RomError::UnsupportedMapper(id)),
         };
 
-        mapper
+        Ok(mapper)
     }
 }
 
 pub trait Mapper {
-    fn cpu_read(&self, addr: u16, mapped_addr: &mut u32) -> bool;
-    fn cpu_write(&mut self, addr: u16, mapped_addr: &mut u32) -> bool;
+    fn cpu_read(&self, addr: u16) -> Mapping;
+    fn cpu_write(&mut self, addr: u16, data: u8) -> Mapping;
+
+    fn ppu_read(&self, addr: u16) -> Mapping;
+    fn ppu_write(&mut self, addr: u16, data: u8) -> Mapping;
+
+    fn mirror(&self) -> Mirror {
+        Mirror::Hardware
+    }
+
+    // Called once per CPU cycle
+    fn cpu_clock(&mut self) {}
+
+    // Called with every address the PPU puts on its bus
+    fn ppu_address(&mut self, _addr: u16) {}
 
-    fn ppu_read(&self, addr: u16, mapped_addr: &mut u32) -> bool;
-    fn ppu_write(&mut self, addr: u16, mapped_addr: &mut u32) -> bool;
+    // Level of the cartridge IRQ line, held until acknowledged through a register
+    fn irq_pending(&self) -> bool {
+        false
+    }
+
+    // Expansion audio mixed with the APU output, in the range 0.0 to 1.0
+    fn audio_output(&self) -> Option<f32> {
+        None
+    }
+
+    // Boards without a ROM /OE gate see the written value ANDed with the ROM byte
+    fn bus_conflicts(&self) -> bool {
+        false
+    }
+}
+
+// NES 2.0 submapper 1 marks boards without bus conflicts and 2 boards with them,
+// otherwise the most common board for the mapper decides
+pub fn has_bus_conflicts(header: &RomHeader, default: bool) -> bool {
+    match header.submapper_id {
+        1 => false,
+        2 => true,
+        _ => default,
+    }
 }
 
 struct Mapper000 {
-    prg_banks: u8,
-    char_banks: u8,
+    prg_rom_size: usize,
+    char_banks: usize,
 }
 
 impl Mapper000 {
-    fn new() -> Mapper000 {
+    fn new(header: &RomHeader) -> Mapper000 {
         Mapper000 {
-            prg_banks: 1,
-            char_banks: 1,
+            prg_rom_size: header.prg_rom_size,
+            char_banks: header.chr_banks(),
         }
     }
 }
 
 impl Mapper for Mapper000 {
-    fn cpu_read(&self, addr: u16, mapped_addr: &mut u32) -> bool {
+    fn cpu_read(&self, addr: u16) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return Mapping::Ram((addr & 0x1FFF) as usize);
+        }
+
+        // Smaller PRG-ROMs are mirrored across $8000-$FFFF
         if addr >= 0x8000 {
-            *mapped_addr = (addr & (if self.prg_banks > 1 { 0x7FFF } else { 0x3FFF })) as u32;
-            return true;
+            return Mapping::Memory((addr & 0x7FFF) as usize % self.prg_rom_size);
         }
-        false
+        Mapping::Unmapped
     }
 
-    fn cpu_write(&mut self, addr: u16, mapped_addr: &mut u32) -> bool {
+    fn cpu_write(&mut self, addr: u16, _data: u8) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return Mapping::Ram((addr & 0x1FFF) as usize);
+        }
+
+        // PRG-ROM can't be written, NROM has no registers either
         if addr >= 0x8000 {
-            *mapped_addr = (addr & (if self.prg_banks > 1 { 0x7FFF } else { 0x3FFF })) as u32;
-            return true;
+            return Mapping::Handled;
         }
-        false
+        Mapping::Unmapped
     }
 
-    fn ppu_read(&self, addr: u16, mapped_addr: &mut u32) -> bool {
+    fn ppu_read(&self, addr: u16) -> Mapping {
         if addr <= 0x1FFF {
-            *mapped_addr = addr as u32;
-            return true;
+            return Mapping::Memory(addr as usize);
         }
-        false
+        Mapping::Unmapped
     }
 
-    fn ppu_write(&mut self, addr: u16, mapped_addr: &mut u32) -> bool {
+    fn ppu_write(&mut self, addr: u16, _data: u8) -> Mapping {
         if addr <= 0x1FFF && self.char_banks == 0 {
             // Treat as RAM
-            *mapped_addr = addr as u32;
-            return true;
+            return Mapping::Memory(addr as usize);
         }
-        false
+        Mapping::Unmapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nrom(prg_size: u8, flags9: u8) -> Mapper000 {
+        Mapper000::new(&RomHeader::from_bytes([
+            b'N', b'E', b'S', 0x1A, prg_size, 0x01, 0x00, 0x08, 0x00, flags9, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00,
+        ]))
+    }
+
+    fn index(mapping: Mapping) -> usize {
+        match mapping {
+            Mapping::Memory(index) => index,
+            _ => panic!("expected a PRG-ROM mapping"),
+        }
+    }
+
+    #[test]
+    fn nrom_mirrors_prg_rom() {
+        let nrom_128 = nrom(1, 0x00);
+        assert_eq!(index(nrom_128.cpu_read(0x8000)), 0x0000);
+        assert_eq!(index(nrom_128.cpu_read(0xFFFC)), 0x3FFC);
+
+        let nrom_256 = nrom(2, 0x00);
+        assert_eq!(index(nrom_256.cpu_read(0xFFFC)), 0x7FFC);
+
+        // NES 2.0 exponent form, 2^13 bytes
+        let nrom_8k = nrom(13 << 2, 0x0F);
+        assert_eq!(index(nrom_8k.cpu_read(0xA000)), 0x0000);
+        assert_eq!(index(nrom_8k.cpu_read(0xFFFC)), 0x1FFC);
     }
 }
@@ -0,0 +1,302 @@
+use super::{
+    header::{RomHeader, CHR_BANK_SIZE, PRG_BANK_SIZE},
+    mapper::{Mapper, Mapping},
+    mirror::Mirror,
+};
+
+const PRG_RAM_BANK_SIZE: usize = 8192;
+const CHR_HALF_BANK_SIZE: usize = 4096;
+
+// MMC1 (SxROM boards)
+// Registers are loaded serially through bit 0 of writes to $8000-$FFFF,
+// the fifth write copies the shift register into the register selected by A13-A14
+pub struct Mapper001 {
+    prg_banks: usize,
+    chr_banks: usize,
+    chr_ram: bool,
+    prg_ram_size: usize,
+
+    shift_register: u8,
+    control: u8,
+    chr_bank_0: u8,
+    chr_bank_1: u8,
+    prg_bank: u8,
+
+    cycle: u64,
+    last_write_cycle: Option<u64>,
+}
+
+impl Mapper001 {
+    pub fn new(header: &RomHeader) -> Mapper001 {
+        let chr_size = if header.chr_rom_size == 0 {
+            (header.chr_ram_size + header.chr_nvram_size).max(CHR_BANK_SIZE)
+        } else {
+            header.chr_rom_size
+        };
+
+        Mapper001 {
+            prg_banks: header.prg_banks().max(1),
+            chr_banks: (chr_size / CHR_HALF_BANK_SIZE).max(1),
+            chr_ram: header.chr_rom_size == 0,
+            prg_ram_size: header.work_ram_size(),
+            shift_register: 0x10,
+            // Power on in PRG mode 3, last bank fixed at $C000
+            control: 0x0C,
+            chr_bank_0: 0x00,
+            chr_bank_1: 0x00,
+            prg_bank: 0x00,
+            cycle: 0,
+            last_write_cycle: None,
+        }
+    }
+
+    fn load_register(&mut self, addr: u16, data: u8) {
+        // Writes on consecutive cycles (e.g. the dummy write of a RMW instruction) are ignored
+        let consecutive = self
+            .last_write_cycle
+            .is_some_and(|last| self.cycle.wrapping_sub(last) <= 1);
+        self.last_write_cycle = Some(self.cycle);
+        if consecutive {
+            return;
+        }
+
+        if (data & 0x80) != 0 {
+            self.shift_register = 0x10;
+            self.control |= 0x0C;
+            return;
+        }
+
+        // The initial 1 reaching bit 0 marks the fifth write
+        let complete = (self.shift_register & 0x01) != 0;
+        self.shift_register = (self.shift_register >> 1) | ((data & 0x01) << 4);
+        if !complete {
+            return;
+        }
+
+        let value = self.shift_register;
+        match addr & 0xE000 {
+            0x8000 => self.control = value,
+            0xA000 => self.chr_bank_0 = value,
+            0xC000 => self.chr_bank_1 = value,
+            _ => self.prg_bank = value,
+        }
+        self.shift_register = 0x10;
+    }
+
+    // SUROM and SXROM use CHR bit 4 to select the 256 KiB half of a 512 KiB PRG-ROM
+    fn prg_outer_bank(&self) -> usize {
+        if self.prg_banks > 16 {
+            return (self.chr_bank_0 & 0x10) as usize;
+        }
+        0
+    }
+
+    fn prg_bank_index(&self, addr: u16) -> usize {
+        let outer = self.prg_outer_bank();
+        let bank = (self.prg_bank & 0x0F) as usize;
+        let high = addr >= 0xC000;
+
+        let index = match (self.control >> 2) & 0x03 {
+            // 32 KiB mode ignores the low bank bit
+            0 | 1 => (bank & 0x0E) | high as usize,
+            // First bank fixed at $8000
+            2 => {
+                if high {
+                    bank
+                } else {
+                    0
+                }
+            }
+            // Last bank fixed at $C000
+            _ => {
+                if high {
+                    0x0F
+                } else {
+                    bank
+                }
+            }
+        };
+
+        (outer | index) % self.prg_banks
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        (self.prg_bank & 0x10) == 0
+    }
+
+    // SOROM uses CHR bit 3 and SXROM bits 2-3 to select an 8 KiB PRG-RAM bank
+    fn prg_ram_index(&self, addr: u16) -> usize {
+        let banks = self.prg_ram_size / PRG_RAM_BANK_SIZE;
+        let bank = match banks {
+            2 => ((self.chr_bank_0 >> 3) & 0x01) as usize,
+            4 => ((self.chr_bank_0 >> 2) & 0x03) as usize,
+            _ => 0,
+        };
+        bank * PRG_RAM_BANK_SIZE + (addr & 0x1FFF) as usize
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        let bank = if (self.control & 0x10) == 0 {
+            // 8 KiB mode ignores the low bank bit
+            (self.chr_bank_0 & 0x1E) as usize | ((addr >> 12) & 0x01) as usize
+        } else if addr < 0x1000 {
+            self.chr_bank_0 as usize
+        } else {
+            self.chr_bank_1 as usize
+        };
+
+        (bank % self.chr_banks) * CHR_HALF_BANK_SIZE + (addr & 0x0FFF) as usize
+    }
+}
+
+impl Mapper for Mapper001 {
+    fn cpu_read(&self, addr: u16) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            if !self.prg_ram_enabled() {
+                return Mapping::Unmapped;
+            }
+            return Mapping::Ram(self.prg_ram_index(addr));
+        }
+
+        if addr >= 0x8000 {
+            return Mapping::Memory(
+                self.prg_bank_index(addr) * PRG_BANK_SIZE + (addr & 0x3FFF) as usize,
+            );
+        }
+        Mapping::Unmapped
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            if !self.prg_ram_enabled() {
+                return Mapping::Unmapped;
+            }
+            return Mapping::Ram(self.prg_ram_index(addr));
+        }
+
+        if addr >= 0x8000 {
+            self.load_register(addr, data);
+            return Mapping::Handled;
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_read(&self, addr: u16) -> Mapping {
+        if addr <= 0x1FFF {
+            return Mapping::Memory(self.chr_index(addr));
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_write(&mut self, addr: u16, _data: u8) -> Mapping {
+        if addr <= 0x1FFF && self.chr_ram {
+            return Mapping::Memory(self.chr_index(addr));
+        }
+        Mapping::Unmapped
+    }
+
+    fn mirror(&self) -> Mirror {
+        match self.control & 0x03 {
+            0 => Mirror::SingleScreenA,
+            1 => Mirror::SingleScreenB,
+            2 => Mirror::Vertical,
+            _ => Mirror::Horizontal,
+        }
+    }
+
+    fn cpu_clock(&mut self) {
+        self.cycle += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 256 KiB PRG-ROM with CHR-RAM
+    fn mmc1() -> Mapper001 {
+        Mapper001::new(&RomHeader::from_bytes([
+            b'N', b'E', b'S', 0x1A, 0x10, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00,
+        ]))
+    }
+
+    fn write(mapper: &mut Mapper001, addr: u16, data: u8) {
+        mapper.cpu_write(addr, data);
+        mapper.cpu_clock();
+        mapper.cpu_clock();
+    }
+
+    // Shifts in the low 5 bits, LSB first
+    fn load(mapper: &mut Mapper001, addr: u16, value: u8) {
+        for bit in 0..5 {
+            write(mapper, addr, (value >> bit) & 0x01);
+        }
+    }
+
+    fn prg_bank(mapper: &Mapper001, addr: u16) -> usize {
+        match mapper.cpu_read(addr) {
+            Mapping::Memory(index) => index / PRG_BANK_SIZE,
+            _ => panic!("expected a PRG-ROM mapping"),
+        }
+    }
+
+    #[test]
+    fn fifth_write_loads_the_register() {
+        let mut mapper = mmc1();
+        for bit in 0..4 {
+            write(&mut mapper, 0xE000, (0x05 >> bit) & 0x01);
+            assert_eq!(prg_bank(&mapper, 0x8000), 0);
+        }
+        write(&mut mapper, 0xE000, 0x00);
+        assert_eq!(prg_bank(&mapper, 0x8000), 5);
+        assert_eq!(prg_bank(&mapper, 0xC000), 15);
+    }
+
+    #[test]
+    fn bit_7_resets_the_shift_register() {
+        let mut mapper = mmc1();
+        load(&mut mapper, 0x8000, 0x02);
+        assert_eq!(mapper.mirror(), Mirror::Vertical);
+
+        write(&mut mapper, 0xE000, 0x01);
+        write(&mut mapper, 0xE000, 0x01);
+        write(&mut mapper, 0xE000, 0x80);
+        load(&mut mapper, 0xE000, 0x03);
+        assert_eq!(prg_bank(&mapper, 0x8000), 3);
+
+        // The reset also went back to PRG mode 3
+        assert_eq!(prg_bank(&mapper, 0xC000), 15);
+        assert_eq!(mapper.mirror(), Mirror::Vertical);
+    }
+
+    #[test]
+    fn prg_modes() {
+        let mut mapper = mmc1();
+        load(&mut mapper, 0xE000, 0x05);
+
+        // First bank fixed at $8000
+        load(&mut mapper, 0x8000, 0x08);
+        assert_eq!(prg_bank(&mapper, 0x8000), 0);
+        assert_eq!(prg_bank(&mapper, 0xC000), 5);
+
+        // 32 KiB mode ignores the low bank bit
+        load(&mut mapper, 0x8000, 0x00);
+        assert_eq!(prg_bank(&mapper, 0x8000), 4);
+        assert_eq!(prg_bank(&mapper, 0xC000), 5);
+    }
+
+    #[test]
+    fn writes_on_consecutive_cycles_are_ignored() {
+        let mut mapper = mmc1();
+        // Dummy write of a RMW instruction followed by the real one,
+        // 6 writes but only 3 of them reach the shift register
+        for _ in 0..3 {
+            mapper.cpu_write(0xE000, 0x01);
+            mapper.cpu_clock();
+            write(&mut mapper, 0xE000, 0x01);
+        }
+        assert_eq!(mapper.shift_register, 0x1E);
+        assert_eq!(mapper.prg_bank, 0x00);
+    }
+}
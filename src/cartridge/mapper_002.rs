@@ -0,0 +1,71 @@
+use super::{
+    header::{RomHeader, PRG_BANK_SIZE},
+    mapper::{has_bus_conflicts, Mapper, Mapping},
+};
+
+// UxROM: switchable 16 KiB bank at $8000, last bank fixed at $C000
+pub struct Mapper002 {
+    prg_banks: usize,
+    chr_ram: bool,
+    bus_conflicts: bool,
+    prg_bank: u8,
+}
+
+impl Mapper002 {
+    pub fn new(header: &RomHeader) -> Mapper002 {
+        Mapper002 {
+            prg_banks: header.prg_banks().max(1),
+            chr_ram: header.chr_rom_size == 0,
+            bus_conflicts: has_bus_conflicts(header, true),
+            prg_bank: 0x00,
+        }
+    }
+}
+
+impl Mapper for Mapper002 {
+    fn cpu_read(&self, addr: u16) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return Mapping::Ram((addr & 0x1FFF) as usize);
+        }
+
+        if addr >= 0x8000 {
+            let bank = if addr >= 0xC000 {
+                self.prg_banks - 1
+            } else {
+                self.prg_bank as usize % self.prg_banks
+            };
+            return Mapping::Memory(bank * PRG_BANK_SIZE + (addr & 0x3FFF) as usize);
+        }
+        Mapping::Unmapped
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return Mapping::Ram((addr & 0x1FFF) as usize);
+        }
+
+        if addr >= 0x8000 {
+            self.prg_bank = data;
+            return Mapping::Handled;
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_read(&self, addr: u16) -> Mapping {
+        if addr <= 0x1FFF {
+            return Mapping::Memory(addr as usize);
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_write(&mut self, addr: u16, _data: u8) -> Mapping {
+        if addr <= 0x1FFF && self.chr_ram {
+            return Mapping::Memory(addr as usize);
+        }
+        Mapping::Unmapped
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
+}
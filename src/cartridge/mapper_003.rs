@@ -0,0 +1,65 @@
+use super::{
+    header::{RomHeader, CHR_BANK_SIZE},
+    mapper::{has_bus_conflicts, Mapper, Mapping},
+};
+
+// CNROM: fixed PRG-ROM like NROM, switchable 8 KiB CHR-ROM bank
+pub struct Mapper003 {
+    prg_rom_size: usize,
+    chr_banks: usize,
+    bus_conflicts: bool,
+    chr_bank: u8,
+}
+
+impl Mapper003 {
+    pub fn new(header: &RomHeader) -> Mapper003 {
+        Mapper003 {
+            prg_rom_size: header.prg_rom_size,
+            chr_banks: header.chr_banks().max(1),
+            bus_conflicts: has_bus_conflicts(header, true),
+            chr_bank: 0x00,
+        }
+    }
+}
+
+impl Mapper for Mapper003 {
+    fn cpu_read(&self, addr: u16) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return Mapping::Ram((addr & 0x1FFF) as usize);
+        }
+
+        // Smaller PRG-ROMs are mirrored across $8000-$FFFF
+        if addr >= 0x8000 {
+            return Mapping::Memory((addr & 0x7FFF) as usize % self.prg_rom_size);
+        }
+        Mapping::Unmapped
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return Mapping::Ram((addr & 0x1FFF) as usize);
+        }
+
+        if addr >= 0x8000 {
+            self.chr_bank = data;
+            return Mapping::Handled;
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_read(&self, addr: u16) -> Mapping {
+        if addr <= 0x1FFF {
+            let bank = self.chr_bank as usize % self.chr_banks;
+            return Mapping::Memory(bank * CHR_BANK_SIZE + addr as usize);
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_write(&mut self, _addr: u16, _data: u8) -> Mapping {
+        Mapping::Unmapped
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
+}
@@ -0,0 +1,407 @@
+use super::{
+    header::RomHeader,
+    mapper::{Mapper, Mapping},
+    mirror::Mirror,
+};
+
+const PRG_BANK_SIZE: usize = 8192;
+const CHR_BANK_SIZE: usize = 1024;
+
+// Minimum number of CPU cycles A12 has to stay low before a rising edge clocks the counter
+const A12_FILTER_CYCLES: u64 = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Revision {
+    // Counter only raises an IRQ when decremented to 0 or reloaded by $C001
+    Mmc3A,
+    // Counter raises an IRQ whenever it is 0 after being clocked
+    Mmc3B,
+    // 1 KiB of internal PRG-RAM with separate protection for each 512 byte half
+    Mmc6,
+}
+
+// MMC3 (TxROM boards) and MMC6 (HKROM)
+pub struct Mapper004 {
+    revision: Revision,
+    prg_rom_size: usize,
+    prg_banks: usize,
+    chr_banks: usize,
+    chr_ram: bool,
+    four_screen: bool,
+
+    bank_select: u8,
+    registers: [u8; 8],
+    mirror: Mirror,
+    prg_ram_protect: u8,
+
+    irq_latch: u8,
+    irq_counter: u8,
+    irq_reload: bool,
+    irq_enabled: bool,
+    irq_pending: bool,
+
+    a12: bool,
+    a12_low_cycle: u64,
+    cycle: u64,
+}
+
+impl Mapper004 {
+    pub fn new(header: &RomHeader) -> Mapper004 {
+        let revision = match header.submapper_id {
+            1 => Revision::Mmc6,
+            4 => Revision::Mmc3A,
+            _ => Revision::Mmc3B,
+        };
+        let chr_size = if header.chr_rom_size == 0 {
+            header.chr_ram_size + header.chr_nvram_size
+        } else {
+            header.chr_rom_size
+        };
+
+        Mapper004 {
+            revision,
+            prg_rom_size: header.prg_rom_size,
+            prg_banks: (header.prg_rom_size / PRG_BANK_SIZE).max(1),
+            chr_banks: (chr_size / CHR_BANK_SIZE).max(1),
+            chr_ram: header.chr_rom_size == 0,
+            four_screen: header.mirror == Mirror::FourScreen,
+            bank_select: 0x00,
+            registers: [0x00, 0x02, 0x04, 0x05, 0x06, 0x07, 0x00, 0x01],
+            mirror: Mirror::Vertical,
+            // Most games expect PRG-RAM to be usable without enabling it first
+            prg_ram_protect: if revision == Revision::Mmc6 {
+                0xF0
+            } else {
+                0x80
+            },
+            irq_latch: 0x00,
+            irq_counter: 0x00,
+            irq_reload: false,
+            irq_enabled: false,
+            irq_pending: false,
+            a12: false,
+            a12_low_cycle: 0,
+            cycle: 0,
+        }
+    }
+
+    fn prg_index(&self, addr: u16) -> usize {
+        let second_last = self.prg_banks.saturating_sub(2);
+        let r6 = (self.registers[6] & 0x3F) as usize;
+        let r7 = (self.registers[7] & 0x3F) as usize;
+        let swap = (self.bank_select & 0x40) != 0;
+
+        let bank = match (addr >> 13) & 0x03 {
+            0 => {
+                if swap {
+                    second_last
+                } else {
+                    r6
+                }
+            }
+            1 => r7,
+            2 => {
+                if swap {
+                    r6
+                } else {
+                    second_last
+                }
+            }
+            _ => self.prg_banks - 1,
+        };
+
+        // PRG-ROMs below 8 KiB only fill part of the single bank
+        ((bank % self.prg_banks) * PRG_BANK_SIZE + (addr & 0x1FFF) as usize) % self.prg_rom_size
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        // CHR inversion swaps the 2 KiB and 1 KiB halves
+        let addr = if (self.bank_select & 0x80) != 0 {
+            addr ^ 0x1000
+        } else {
+            addr
+        };
+
+        let bank = match addr >> 10 {
+            0 => (self.registers[0] & 0xFE) as usize,
+            1 => (self.registers[0] | 0x01) as usize,
+            2 => (self.registers[1] & 0xFE) as usize,
+            3 => (self.registers[1] | 0x01) as usize,
+            slot => self.registers[(slot - 2) as usize] as usize,
+        };
+
+        (bank % self.chr_banks) * CHR_BANK_SIZE + (addr & 0x03FF) as usize
+    }
+
+    // Returns the RAM index and whether the access is allowed, None for open bus
+    fn prg_ram_access(&self, addr: u16, write: bool) -> Option<(usize, bool)> {
+        if self.revision != Revision::Mmc6 {
+            let enabled = (self.prg_ram_protect & 0x80) != 0;
+            let writable = (self.prg_ram_protect & 0x40) == 0;
+            if !enabled || (write && !writable) {
+                return None;
+            }
+            return Some(((addr & 0x1FFF) as usize, true));
+        }
+
+        // MMC6 RAM only lives at $7000-$7FFF and must be enabled through $8000 first
+        if addr < 0x7000 || (self.bank_select & 0x20) == 0 {
+            return None;
+        }
+        let index = (addr & 0x03FF) as usize;
+        let shift = if index >= 0x0200 { 6 } else { 4 };
+        let readable = (self.prg_ram_protect >> (shift + 1)) & 0x01 != 0;
+        let writable = (self.prg_ram_protect >> shift) & 0x01 != 0;
+        if write {
+            return if readable && writable {
+                Some((index, true))
+            } else {
+                None
+            };
+        }
+        // Reads return open bus only if neither half is readable
+        if (self.prg_ram_protect & 0xA0) == 0 {
+            return None;
+        }
+        Some((index, readable))
+    }
+
+    fn write_register(&mut self, addr: u16, data: u8) {
+        let even = (addr & 0x0001) == 0;
+        match (addr & 0xE000, even) {
+            (0x8000, true) => self.bank_select = data,
+            (0x8000, false) => self.registers[(self.bank_select & 0x07) as usize] = data,
+            (0xA000, true) => {
+                self.mirror = if (data & 0x01) != 0 {
+                    Mirror::Horizontal
+                } else {
+                    Mirror::Vertical
+                }
+            }
+            (0xA000, false) => {
+                if self.revision != Revision::Mmc6 || (self.bank_select & 0x20) != 0 {
+                    self.prg_ram_protect = data;
+                }
+            }
+            (0xC000, true) => self.irq_latch = data,
+            (0xC000, false) => {
+                self.irq_counter = 0;
+                self.irq_reload = true;
+            }
+            (_, true) => {
+                self.irq_enabled = false;
+                self.irq_pending = false;
+            }
+            (_, false) => self.irq_enabled = true,
+        }
+    }
+
+    fn clock_irq_counter(&mut self) {
+        let previous = self.irq_counter;
+        let reload = self.irq_reload;
+        if self.irq_counter == 0 || self.irq_reload {
+            self.irq_counter = self.irq_latch;
+        } else {
+            self.irq_counter -= 1;
+        }
+        self.irq_reload = false;
+
+        let trigger = match self.revision {
+            Revision::Mmc3A => self.irq_counter == 0 && (previous != 0 || reload),
+            _ => self.irq_counter == 0,
+        };
+        if trigger && self.irq_enabled {
+            self.irq_pending = true;
+        }
+    }
+}
+
+impl Mapper for Mapper004 {
+    fn cpu_read(&self, addr: u16) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return match self.prg_ram_access(addr, false) {
+                Some((index, true)) => Mapping::Ram(index),
+                Some((_, false)) => Mapping::Data(0x00),
+                None => Mapping::Unmapped,
+            };
+        }
+
+        if addr >= 0x8000 {
+            return Mapping::Memory(self.prg_index(addr));
+        }
+        Mapping::Unmapped
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return match self.prg_ram_access(addr, true) {
+                Some((index, _)) => Mapping::Ram(index),
+                None => Mapping::Unmapped,
+            };
+        }
+
+        if addr >= 0x8000 {
+            self.write_register(addr, data);
+            return Mapping::Handled;
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_read(&self, addr: u16) -> Mapping {
+        if addr <= 0x1FFF {
+            return Mapping::Memory(self.chr_index(addr));
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_write(&mut self, addr: u16, _data: u8) -> Mapping {
+        if addr <= 0x1FFF && self.chr_ram {
+            return Mapping::Memory(self.chr_index(addr));
+        }
+        Mapping::Unmapped
+    }
+
+    fn mirror(&self) -> Mirror {
+        if self.four_screen {
+            return Mirror::Hardware;
+        }
+        self.mirror
+    }
+
+    fn cpu_clock(&mut self) {
+        self.cycle += 1;
+    }
+
+    // The counter is clocked by filtered rising edges of PPU A12,
+    // normally once per scanline when fetching sprites from $1000
+    fn ppu_address(&mut self, addr: u16) {
+        let a12 = (addr & 0x1000) != 0;
+        if a12 && !self.a12 && self.cycle - self.a12_low_cycle >= A12_FILTER_CYCLES {
+            self.clock_irq_counter();
+        } else if !a12 && self.a12 {
+            self.a12_low_cycle = self.cycle;
+        }
+        self.a12 = a12;
+    }
+
+    fn irq_pending(&self) -> bool {
+        self.irq_pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mmc3(submapper: u8) -> Mapper004 {
+        // NES 2.0, 32 KiB PRG-ROM and 8 KiB CHR-ROM
+        let mut header = [0x00; 16];
+        header[0..8].copy_from_slice(&[b'N', b'E', b'S', 0x1A, 0x02, 0x01, 0x40, 0x08]);
+        header[8] = submapper << 4;
+        Mapper004::new(&RomHeader::from_bytes(header))
+    }
+
+    // Sets the latch, reloads the counter and enables IRQs
+    fn setup_irq(mapper: &mut Mapper004, latch: u8) {
+        mapper.cpu_write(0xC000, latch);
+        mapper.cpu_write(0xC001, 0x00);
+        mapper.cpu_write(0xE001, 0x00);
+    }
+
+    // A12 low for the given number of CPU cycles, then a rising edge
+    fn a12_edge(mapper: &mut Mapper004, low_cycles: u64) {
+        mapper.ppu_address(0x0000);
+        for _ in 0..low_cycles {
+            mapper.cpu_clock();
+        }
+        mapper.ppu_address(0x1000);
+    }
+
+    fn scanline(mapper: &mut Mapper004) {
+        a12_edge(mapper, 10);
+    }
+
+    #[test]
+    fn counter_counts_down_from_the_latch() {
+        let mut mapper = mmc3(0);
+        setup_irq(&mut mapper, 2);
+
+        scanline(&mut mapper);
+        assert_eq!(mapper.irq_counter, 2);
+        scanline(&mut mapper);
+        assert!(!mapper.irq_pending());
+        scanline(&mut mapper);
+        assert!(mapper.irq_pending());
+
+        // $E000 acknowledges and disables
+        mapper.cpu_write(0xE000, 0x00);
+        assert!(!mapper.irq_pending());
+        for _ in 0..3 {
+            scanline(&mut mapper);
+        }
+        assert!(!mapper.irq_pending());
+    }
+
+    #[test]
+    fn a12_filter_ignores_short_low_periods() {
+        let mut mapper = mmc3(0);
+        setup_irq(&mut mapper, 5);
+        scanline(&mut mapper);
+        assert_eq!(mapper.irq_counter, 5);
+
+        // e.g. the background and sprite fetches alternating within 8x16 sprites
+        a12_edge(&mut mapper, A12_FILTER_CYCLES - 1);
+        assert_eq!(mapper.irq_counter, 5);
+        a12_edge(&mut mapper, A12_FILTER_CYCLES);
+        assert_eq!(mapper.irq_counter, 4);
+
+        // Staying high doesn't clock again
+        mapper.ppu_address(0x1FFF);
+        assert_eq!(mapper.irq_counter, 4);
+    }
+
+    #[test]
+    fn latch_zero_depends_on_revision() {
+        let mut mmc3b = mmc3(0);
+        let mut mmc3a = mmc3(4);
+        setup_irq(&mut mmc3b, 0);
+        setup_irq(&mut mmc3a, 0);
+
+        // The reload to 0 raises an IRQ on both
+        scanline(&mut mmc3b);
+        scanline(&mut mmc3a);
+        assert!(mmc3b.irq_pending());
+        assert!(mmc3a.irq_pending());
+
+        mmc3b.cpu_write(0xE000, 0x00);
+        mmc3b.cpu_write(0xE001, 0x00);
+        mmc3a.cpu_write(0xE000, 0x00);
+        mmc3a.cpu_write(0xE001, 0x00);
+
+        // Only the MMC3B keeps firing while the counter stays at 0
+        scanline(&mut mmc3b);
+        scanline(&mut mmc3a);
+        assert!(mmc3b.irq_pending());
+        assert!(!mmc3a.irq_pending());
+    }
+
+    #[test]
+    fn prg_banks_with_fixed_last_bank() {
+        let mut mapper = mmc3(0);
+        mapper.cpu_write(0x8000, 0x06);
+        mapper.cpu_write(0x8001, 0x01);
+
+        let bank = |mapper: &Mapper004, addr| match mapper.cpu_read(addr) {
+            Mapping::Memory(index) => index / PRG_BANK_SIZE,
+            _ => panic!("expected a PRG-ROM mapping"),
+        };
+        assert_eq!(bank(&mapper, 0x8000), 1);
+        assert_eq!(bank(&mapper, 0xC000), 2);
+        assert_eq!(bank(&mapper, 0xE000), 3);
+
+        // PRG mode 1 swaps $8000 and $C000
+        mapper.cpu_write(0x8000, 0x46);
+        assert_eq!(bank(&mapper, 0x8000), 2);
+        assert_eq!(bank(&mapper, 0xC000), 1);
+    }
+}
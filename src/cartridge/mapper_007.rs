@@ -0,0 +1,82 @@
+use super::{
+    header::RomHeader,
+    mapper::{has_bus_conflicts, Mapper, Mapping},
+    mirror::Mirror,
+};
+
+const PRG_BANK_SIZE: usize = 32768;
+
+// AxROM: switchable 32 KiB PRG-ROM bank and single-screen mirroring
+pub struct Mapper007 {
+    prg_banks: usize,
+    chr_ram: bool,
+    bus_conflicts: bool,
+    prg_bank: u8,
+    mirror: Mirror,
+}
+
+impl Mapper007 {
+    pub fn new(header: &RomHeader) -> Mapper007 {
+        Mapper007 {
+            prg_banks: (header.prg_rom_size / PRG_BANK_SIZE).max(1),
+            chr_ram: header.chr_rom_size == 0,
+            // Only AMROM has bus conflicts, ANROM and AOROM do not
+            bus_conflicts: has_bus_conflicts(header, false),
+            prg_bank: 0x00,
+            mirror: Mirror::SingleScreenA,
+        }
+    }
+}
+
+impl Mapper for Mapper007 {
+    fn cpu_read(&self, addr: u16) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return Mapping::Ram((addr & 0x1FFF) as usize);
+        }
+
+        if addr >= 0x8000 {
+            let bank = self.prg_bank as usize % self.prg_banks;
+            return Mapping::Memory(bank * PRG_BANK_SIZE + (addr & 0x7FFF) as usize);
+        }
+        Mapping::Unmapped
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return Mapping::Ram((addr & 0x1FFF) as usize);
+        }
+
+        if addr >= 0x8000 {
+            self.prg_bank = data & 0x07;
+            self.mirror = if (data & 0x10) != 0 {
+                Mirror::SingleScreenB
+            } else {
+                Mirror::SingleScreenA
+            };
+            return Mapping::Handled;
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_read(&self, addr: u16) -> Mapping {
+        if addr <= 0x1FFF {
+            return Mapping::Memory(addr as usize);
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_write(&mut self, addr: u16, _data: u8) -> Mapping {
+        if addr <= 0x1FFF && self.chr_ram {
+            return Mapping::Memory(addr as usize);
+        }
+        Mapping::Unmapped
+    }
+
+    fn mirror(&self) -> Mirror {
+        self.mirror
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
+}
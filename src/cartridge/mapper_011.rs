@@ -0,0 +1,70 @@
+use super::{
+    header::{RomHeader, CHR_BANK_SIZE},
+    mapper::{has_bus_conflicts, Mapper, Mapping},
+};
+
+const PRG_BANK_SIZE: usize = 32768;
+
+// Color Dreams: switchable 32 KiB PRG-ROM bank in bits 0-1, 8 KiB CHR-ROM bank in bits 4-7
+pub struct Mapper011 {
+    prg_banks: usize,
+    chr_banks: usize,
+    bus_conflicts: bool,
+    prg_bank: u8,
+    chr_bank: u8,
+}
+
+impl Mapper011 {
+    pub fn new(header: &RomHeader) -> Mapper011 {
+        Mapper011 {
+            prg_banks: (header.prg_rom_size / PRG_BANK_SIZE).max(1),
+            chr_banks: header.chr_banks().max(1),
+            bus_conflicts: has_bus_conflicts(header, true),
+            prg_bank: 0x00,
+            chr_bank: 0x00,
+        }
+    }
+}
+
+impl Mapper for Mapper011 {
+    fn cpu_read(&self, addr: u16) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return Mapping::Ram((addr & 0x1FFF) as usize);
+        }
+
+        if addr >= 0x8000 {
+            let bank = self.prg_bank as usize % self.prg_banks;
+            return Mapping::Memory(bank * PRG_BANK_SIZE + (addr & 0x7FFF) as usize);
+        }
+        Mapping::Unmapped
+    }
+
+    fn cpu_write(&mut self, addr: u16, data: u8) -> Mapping {
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return Mapping::Ram((addr & 0x1FFF) as usize);
+        }
+
+        if addr >= 0x8000 {
+            self.prg_bank = data & 0x03;
+            self.chr_bank = data >> 4;
+            return Mapping::Handled;
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_read(&self, addr: u16) -> Mapping {
+        if addr <= 0x1FFF {
+            let bank = self.chr_bank as usize % self.chr_banks;
+            return Mapping::Memory(bank * CHR_BANK_SIZE + addr as usize);
+        }
+        Mapping::Unmapped
+    }
+
+    fn ppu_write(&mut self, _addr: u16, _data: u8) -> Mapping {
+        Mapping::Unmapped
+    }
+
+    fn bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
+}
@@ -0,0 +1,10 @@
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Mirror {
+    // Use the arrangement soldered on the cartridge, as reported by the header
+    Hardware,
+    Horizontal,
+    Vertical,
+    SingleScreenA,
+    SingleScreenB,
+    FourScreen,
+}
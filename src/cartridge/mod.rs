@@ -1,3 +1,13 @@
 pub mod cartridge;
+pub mod header;
+pub mod mirror;
+pub mod rom_error;
 
 mod mapper;
+mod mapper_001;
+mod mapper_002;
+mod mapper_003;
+mod mapper_004;
+mod mapper_007;
+mod mapper_011;
+mod mapper_066;
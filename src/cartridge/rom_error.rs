@@ -0,0 +1,53 @@
+use std::{error::Error, fmt, io};
+
+#[derive(Debug)]
+pub enum RomError {
+    Io(io::Error),
+    BadMagic,
+    EmptyPrg,
+    TruncatedPrg { expected: usize, found: usize },
+    TruncatedChr { expected: usize, found: usize },
+    UnsupportedMapper(u16),
+    TrailingData(usize),
+}
+
+impl fmt::Display for RomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RomError::Io(err) => write!(f, "Failed reading the cartridge file: {}", err),
+            RomError::BadMagic => write!(f, "Not an iNES file, the header signature is missing"),
+            RomError::EmptyPrg => write!(f, "The header declares no PRG-ROM"),
+            RomError::TruncatedPrg { expected, found } => write!(
+                f,
+                "PRG-ROM is truncated: expected {} bytes, found {}",
+                expected, found
+            ),
+            RomError::TruncatedChr { expected, found } => write!(
+                f,
+                "CHR-ROM is truncated: expected {} bytes, found {}",
+                expected, found
+            ),
+            RomError::UnsupportedMapper(id) => write!(f, "Mapper {} is not supported", id),
+            RomError::TrailingData(size) => write!(
+                f,
+                "{} bytes of unexpected data after CHR-ROM, the header is likely wrong",
+                size
+            ),
+        }
+    }
+}
+
+impl Error for RomError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RomError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RomError {
+    fn from(err: io::Error) -> RomError {
+        RomError::Io(err)
+    }
+}
@@ -1,6 +1,15 @@
 pub mod emulator {
     pub const GAME_ID: &str = "RustyNes";
     pub const AUTHOR_NAME: &str = "Nikolai Prjanikov";
+
+    pub const WINDOW_WIDTH: f32 = 1280.0;
+    pub const WINDOW_HEIGHT: f32 = 720.0;
+}
+
+pub mod audio {
+
+    pub const CPU_CLOCK_RATE: f64 = 1_789_773.0;
+    pub const SAMPLE_RATE: u32 = 48000;
 }
 
 pub mod cpu {
@@ -13,8 +22,25 @@ pub mod cpu {
     pub const START_ADDR: u16 = 0xFFFC;
 }
 
+pub mod apu {
+
+    pub const REGISTER_ADDR_MIN: u16 = 0x4000;
+    pub const REGISTER_ADDR_MAX: u16 = 0x4013;
+    pub const STATUS_ADDR: u16 = 0x4015;
+    pub const FRAME_COUNTER_ADDR: u16 = 0x4017;
+}
+
+pub mod controller {
+
+    pub const PORT_1_ADDR: u16 = 0x4016;
+    pub const PORT_2_ADDR: u16 = 0x4017;
+}
+
 pub mod ppu {
 
+    pub const SCREEN_WIDTH: usize = 256;
+    pub const SCREEN_HEIGHT: usize = 240;
+
     pub const RAM_ADDR_MAX: u16 = 0x3FFF;
     pub const RAM_ADDR_MIN: u16 = 0x2000;
 
@@ -26,4 +52,77 @@ pub mod ppu {
     pub const SCROLL_ADDR: u16 = 0x0005;
     pub const PPUADDRESS_ADDR: u16 = 0x0006;
     pub const PPUDATA_ADDR: u16 = 0x0007;
+    pub const OAM_DMA_ADDR: u16 = 0x4014;
+
+    pub const NAMETABLE_ADDR: u16 = 0x2000;
+    pub const ATTRIBUTE_ADDR: u16 = 0x23C0;
+    pub const PALETTE_ADDR: u16 = 0x3F00;
+
+    // 2C02 system palette
+    pub const PALETTE: [(u8, u8, u8); 0x40] = [
+        (84, 84, 84),
+        (0, 30, 116),
+        (8, 16, 144),
+        (48, 0, 136),
+        (68, 0, 100),
+        (92, 0, 48),
+        (84, 4, 0),
+        (60, 24, 0),
+        (32, 42, 0),
+        (8, 58, 0),
+        (0, 64, 0),
+        (0, 60, 0),
+        (0, 50, 60),
+        (0, 0, 0),
+        (0, 0, 0),
+        (0, 0, 0),
+        (152, 150, 152),
+        (8, 76, 196),
+        (48, 50, 236),
+        (92, 30, 228),
+        (136, 20, 176),
+        (160, 20, 100),
+        (152, 34, 32),
+        (120, 60, 0),
+        (84, 90, 0),
+        (40, 114, 0),
+        (8, 124, 0),
+        (0, 118, 40),
+        (0, 102, 120),
+        (0, 0, 0),
+        (0, 0, 0),
+        (0, 0, 0),
+        (236, 238, 236),
+        (76, 154, 236),
+        (120, 124, 236),
+        (176, 98, 236),
+        (228, 84, 236),
+        (236, 88, 180),
+        (236, 106, 100),
+        (212, 136, 32),
+        (160, 170, 0),
+        (116, 196, 0),
+        (76, 208, 32),
+        (56, 204, 108),
+        (56, 180, 204),
+        (60, 60, 60),
+        (0, 0, 0),
+        (0, 0, 0),
+        (236, 238, 236),
+        (168, 204, 236),
+        (188, 188, 236),
+        (212, 178, 236),
+        (236, 174, 236),
+        (236, 174, 212),
+        (236, 180, 176),
+        (228, 196, 144),
+        (204, 210, 120),
+        (180, 222, 120),
+        (168, 226, 144),
+        (152, 226, 180),
+        (160, 214, 228),
+        (160, 162, 160),
+        (0, 0, 0),
+        (0, 0, 0),
+    ];
 }
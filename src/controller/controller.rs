@@ -0,0 +1,59 @@
+use bitflags::bitflags;
+
+bitflags! {
+
+    // Buttons in the order they are shifted out
+    #[derive(Clone, Copy, PartialEq)]
+    pub struct Button: u8 {
+        const A = (1 << 0);
+        const B = (1 << 1);
+        const SELECT = (1 << 2);
+        const START = (1 << 3);
+        const UP = (1 << 4);
+        const DOWN = (1 << 5);
+        const LEFT = (1 << 6);
+        const RIGHT = (1 << 7);
+    }
+
+}
+
+// Standard controller, a 4021 parallel-in serial-out shift register
+pub struct Controller {
+    buttons: Button,
+    shift_register: u8,
+    strobe: bool,
+}
+
+impl Controller {
+    pub fn new() -> Controller {
+        Controller {
+            buttons: Button::empty(),
+            shift_register: 0x00,
+            strobe: false,
+        }
+    }
+
+    pub fn set_buttons(&mut self, buttons: Button) {
+        self.buttons = buttons;
+    }
+
+    // While strobe is high the shift register keeps reloading the button state
+    pub fn write(&mut self, data: u8) {
+        self.strobe = (data & 0x01) != 0;
+        if self.strobe {
+            self.shift_register = self.buttons.bits();
+        }
+    }
+
+    // Returns the next button in bit 0, official controllers return 1 after all 8 are read
+    pub fn read(&mut self, readonly: bool) -> u8 {
+        if self.strobe {
+            self.shift_register = self.buttons.bits();
+        }
+        let data = self.shift_register & 0x01;
+        if !readonly && !self.strobe {
+            self.shift_register = (self.shift_register >> 1) | 0x80;
+        }
+        data
+    }
+}
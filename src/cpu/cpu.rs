@@ -3,7 +3,10 @@ use crate::constants;
 
 use super::{
     cpu_flags::CpuFlags,
-    operations::{addrmode::AddrMode, instruction::Instruction},
+    operations::{
+        addrmode::AddrMode,
+        instruction::{Access, Instruction},
+    },
 };
 
 pub struct Cpu {
@@ -22,6 +25,7 @@ pub struct Cpu {
     // Internal Helpers
     pub fetched: u8,
     pub temp: u16,
+    pub branch_taken: bool,
 
     // Addresses
     pub addr_abs: u16,
@@ -29,9 +33,20 @@ pub struct Cpu {
 
     // Current OP Code
     pub opcode: u8,
+    // Set while the BRK sequence runs for an IRQ or NMI instead
+    pub interrupt: bool,
+
+    // Interrupt polling, sampled at the end of every cycle
+    pub need_nmi: bool,
+    pub prev_need_nmi: bool,
+    pub run_irq: bool,
+    pub prev_run_irq: bool,
+    // Set on the cycle of a taken branch that skips the poll
+    pub skip_poll: bool,
 
     // Timing
-    pub cycles: u8,
+    // Cycle of the current instruction, 0 between instructions
+    pub step: u8,
     pub clock_count: u32,
 
     // Connected Data Bus
@@ -49,10 +64,17 @@ impl Cpu {
             status: 0x00,
             fetched: 0x00,
             temp: 0x0000,
+            branch_taken: false,
             addr_abs: 0x0000,
             addr_rel: 0x0000,
             opcode: 0x00,
-            cycles: 0,
+            interrupt: false,
+            need_nmi: false,
+            prev_need_nmi: false,
+            run_irq: false,
+            prev_run_irq: false,
+            skip_poll: false,
+            step: 0,
             clock_count: 0,
             bus: Box::new(CpuBus::new()),
         }
@@ -73,131 +95,497 @@ impl Cpu {
         self.addr_rel = 0x0000;
         self.addr_abs = 0x0000;
         self.fetched = 0x00;
-        self.cycles = 0;
+        self.interrupt = false;
+        self.need_nmi = false;
+        self.prev_need_nmi = false;
+        self.run_irq = false;
+        self.prev_run_irq = false;
+        self.skip_poll = false;
+        self.step = 0;
     }
 
-    #[allow(unused)] // TODO: Remove unused
-    fn irq(&mut self) {
-        if self.get_flag(CpuFlags::I) != 0 {
-            return;
+    pub fn get_flag(&self, flag: CpuFlags) -> u8 {
+        let CpuFlags(mask) = flag;
+
+        if self.status & mask > 0 {
+            1
+        } else {
+            0
         }
-        // Save PC on stack
+    }
 
-        self.bus.write(
-            constants::cpu::STACK_BASE_ADDR + (self.stkp as u16),
-            ((self.pc >> 8) & 0x00FF) as u8,
-        );
-        self.stkp -= 1;
+    pub fn set_flag(&mut self, flag: CpuFlags, value: bool) {
+        let CpuFlags(mask) = flag;
 
-        self.bus.write(
-            constants::cpu::STACK_BASE_ADDR + (self.stkp as u16),
-            (self.pc & 0x00FF) as u8,
-        );
-        self.stkp -= 1;
+        if value {
+            self.status |= mask;
+        } else {
+            self.status &= !mask;
+        }
+    }
 
-        self.set_flag(CpuFlags::B, false);
-        self.set_flag(CpuFlags::U, true);
-        self.set_flag(CpuFlags::I, true);
+    // Read operators fetch their operand on their last cycle,
+    // read-modify-write operators get the value read in the cycles before
+    pub fn fetch(&mut self) -> u8 {
+        if Instruction::from_opcode(self.opcode).get_access() == Access::Read {
+            self.fetched = self.bus.read(self.addr_abs);
+        }
+        self.fetched
+    }
 
-        self.bus.write(
-            constants::cpu::STACK_BASE_ADDR + (self.stkp as u16),
-            self.status,
-        );
-        self.stkp -= 1;
+    // Whether the coming cycle reads the bus, writes are stack pushes
+    // and the last cycles of store and read-modify-write instructions
+    pub fn next_cycle_reads(&self) -> bool {
+        let step = self.step + 1;
+        if step == 1 {
+            return true;
+        }
+        let instr = Instruction::from_opcode(self.opcode);
+        match self.opcode {
+            0x00 => !(3..=5).contains(&step),
+            0x20 => !(4..=5).contains(&step),
+            0x08 | 0x48 => step != 3,
+            _ => match instr.get_access() {
+                Access::Write => step != instr.get_cycles(),
+                Access::ReadModifyWrite => step < instr.get_cycles() - 1,
+                _ => true,
+            },
+        }
+    }
 
-        self.addr_abs = 0xFFFE;
+    // Runs a single cycle with exactly the bus access the 6502 does in it
+    pub fn clock(&mut self) {
+        self.step += 1;
+        let done = if self.step == 1 {
+            self.fetch_opcode();
+            false
+        } else {
+            self.execute_cycle()
+        };
+        self.poll_interrupts();
+        if done {
+            self.step = 0;
+        }
 
-        let lo: u16 = self.bus.read(self.addr_abs) as u16;
-        let hi: u16 = self.bus.read(self.addr_abs + 1) as u16;
+        self.clock_count += 1;
+    }
 
-        self.pc = (hi << 8) | lo;
-        self.cycles = 7;
+    pub fn clock_ppu(&mut self) {
+        self.bus.ppu.clock()
     }
 
-    #[allow(unused)] // TODO: Remove unused
-    fn nmi(&mut self) {
-        self.bus.write(
-            constants::cpu::STACK_BASE_ADDR + (self.stkp as u16),
-            ((self.pc >> 8) & 0x00FF) as u8,
-        );
-        self.stkp -= 1;
+    // Interrupts are only checked between instructions, against what was sampled
+    // on the second to last cycle, so the I flag set by CLI, SEI and PLP takes
+    // effect one instruction late
+    fn poll_interrupts(&mut self) {
+        // A skipped poll only keeps what was already seen the cycle before
+        let skip = std::mem::take(&mut self.skip_poll);
+
+        self.prev_need_nmi = self.need_nmi && (self.prev_need_nmi || !skip);
+        // NMI is edge triggered, the PPU latches the edge until it's taken
+        if self.bus.poll_nmi() {
+            self.need_nmi = true;
+        }
 
-        self.bus.write(
-            constants::cpu::STACK_BASE_ADDR + (self.stkp as u16),
-            (self.pc & 0x00FF) as u8,
-        );
-        self.stkp -= 1;
+        self.prev_run_irq = self.run_irq && (self.prev_run_irq || !skip);
+        self.run_irq = self.bus.irq.asserted() && self.get_flag(CpuFlags::I) == 0;
+    }
 
-        self.set_flag(CpuFlags::B, false);
+    fn fetch_opcode(&mut self) {
+        self.interrupt = self.prev_need_nmi || self.prev_run_irq;
+        if self.interrupt {
+            // Interrupts run the BRK sequence with the opcode fetch turned into a dummy read
+            self.bus.read(self.pc);
+            self.opcode = 0x00;
+        } else {
+            self.opcode = self.bus.read(self.pc);
+            self.pc = self.pc.wrapping_add(1);
+        }
         self.set_flag(CpuFlags::U, true);
-        self.set_flag(CpuFlags::I, true);
+    }
 
-        self.bus.write(
-            constants::cpu::STACK_BASE_ADDR + (self.stkp as u16),
-            self.status,
-        );
-        self.stkp -= 1;
+    // Returns true on the last cycle of the instruction
+    fn execute_cycle(&mut self) -> bool {
+        let instr = Instruction::from_opcode(self.opcode);
+        match self.opcode {
+            0x00 => self.interrupt_cycle(instr),
+            0x20 => self.jsr_cycle(instr),
+            0x40 => self.rti_cycle(instr),
+            0x60 => self.rts_cycle(instr),
+            0x08 | 0x48 => self.push_cycle(instr),
+            0x28 | 0x68 => self.pull_cycle(instr),
+            _ => match instr.get_addrmode() {
+                AddrMode::IMP => {
+                    self.bus.read(self.pc);
+                    self.fetched = self.a;
+                    self.operate(instr)
+                }
+                AddrMode::IMM => {
+                    self.addr_abs = self.pc;
+                    self.pc = self.pc.wrapping_add(1);
+                    self.operate(instr)
+                }
+                AddrMode::ZP0 => self.zero_page_cycle(instr, None),
+                AddrMode::ZPX => self.zero_page_cycle(instr, Some(self.x)),
+                AddrMode::ZPY => self.zero_page_cycle(instr, Some(self.y)),
+                AddrMode::REL => self.branch_cycle(instr),
+                AddrMode::ABS => self.absolute_cycle(instr),
+                AddrMode::ABX => self.absolute_indexed_cycle(instr, self.x),
+                AddrMode::ABY => self.absolute_indexed_cycle(instr, self.y),
+                AddrMode::IND => self.indirect_cycle(instr),
+                AddrMode::IZX => self.indexed_indirect_cycle(instr),
+                AddrMode::IZY => self.indirect_indexed_cycle(instr),
+            },
+        }
+    }
 
-        self.addr_abs = 0xFFFA;
+    fn operate(&mut self, instr: &Instruction) -> bool {
+        (instr.operator)(self);
+        true
+    }
 
-        let lo: u16 = self.bus.read(self.addr_abs) as u16;
-        let hi: u16 = self.bus.read(self.addr_abs + 1) as u16;
+    fn read_pc(&mut self) -> u8 {
+        let data = self.bus.read(self.pc);
+        self.pc = self.pc.wrapping_add(1);
+        data
+    }
 
-        self.pc = (hi << 8) | lo;
-        self.cycles = 8;
+    fn push(&mut self, data: u8) {
+        self.bus
+            .write(constants::cpu::STACK_BASE_ADDR + (self.stkp as u16), data);
+        self.stkp = self.stkp.wrapping_sub(1);
     }
 
-    pub fn get_flag(&self, flag: CpuFlags) -> u8 {
-        let CpuFlags(mask) = flag;
+    fn pull(&mut self) -> u8 {
+        self.stkp = self.stkp.wrapping_add(1);
+        self.bus
+            .read(constants::cpu::STACK_BASE_ADDR + (self.stkp as u16))
+    }
 
-        if self.status & mask > 0 {
-            1
-        } else {
-            0
+    // Cycles once the effective address is known, starting at the given step
+    fn access_cycle(&mut self, instr: &Instruction, first: u8) -> bool {
+        match (instr.get_access(), self.step - first) {
+            (Access::ReadModifyWrite, 0) => {
+                self.fetched = self.bus.read(self.addr_abs);
+                false
+            }
+            // The unmodified value is written back while the ALU works on it
+            (Access::ReadModifyWrite, 1) => {
+                self.bus.write(self.addr_abs, self.fetched);
+                false
+            }
+            _ => self.operate(instr),
         }
     }
 
-    pub fn set_flag(&mut self, flag: CpuFlags, value: bool) {
-        let CpuFlags(mask) = flag;
+    // Indexed modes first access the address before the carry reached the high byte,
+    // reads only spend an extra cycle on the corrected address if the page was crossed
+    fn index_cycle(&mut self, instr: &Instruction, first: u8) -> bool {
+        if self.step == first && instr.get_access() == Access::Read && self.temp == self.addr_abs {
+            return self.operate(instr);
+        }
+        if self.step == first {
+            self.bus.read(self.temp);
+            return false;
+        }
+        self.access_cycle(instr, first + 1)
+    }
 
-        if value {
-            self.status |= mask;
-        } else {
-            self.status &= !mask;
+    fn add_index(&mut self, base: u16, index: u8) {
+        self.addr_abs = base.wrapping_add(index as u16);
+        self.temp = (base & 0xFF00) | (self.addr_abs & 0x00FF);
+    }
+
+    fn zero_page_cycle(&mut self, instr: &Instruction, index: Option<u8>) -> bool {
+        match (self.step, index) {
+            (2, _) => {
+                self.addr_abs = self.read_pc() as u16;
+                false
+            }
+            (3, Some(index)) => {
+                self.bus.read(self.addr_abs);
+                self.addr_abs = (self.addr_abs + index as u16) & 0x00FF;
+                false
+            }
+            (_, Some(_)) => self.access_cycle(instr, 4),
+            (_, None) => self.access_cycle(instr, 3),
         }
     }
 
-    pub fn fetch(&mut self) -> u8 {
-        if !matches!(
-            Instruction::from_opcode(self.opcode).get_addrmode(),
-            AddrMode::IMP
-        ) {
-            self.fetched = self.bus.read(self.addr_abs);
+    fn absolute_cycle(&mut self, instr: &Instruction) -> bool {
+        match self.step {
+            2 => {
+                self.addr_abs = self.read_pc() as u16;
+                false
+            }
+            3 => {
+                self.addr_abs |= (self.read_pc() as u16) << 8;
+                // JMP is done as soon as it has the address
+                if instr.get_access() == Access::None {
+                    return self.operate(instr);
+                }
+                false
+            }
+            _ => self.access_cycle(instr, 4),
         }
-        self.fetched
     }
 
-    pub fn clock(&mut self) {
-        if self.cycles <= 0 {
-            self.opcode = self.bus.read(self.pc);
-            self.set_flag(CpuFlags::U, true);
-            self.pc += 1;
+    fn absolute_indexed_cycle(&mut self, instr: &Instruction, index: u8) -> bool {
+        match self.step {
+            2 => {
+                self.addr_abs = self.read_pc() as u16;
+                false
+            }
+            3 => {
+                let base = ((self.read_pc() as u16) << 8) | self.addr_abs;
+                self.add_index(base, index);
+                false
+            }
+            _ => self.index_cycle(instr, 4),
+        }
+    }
 
-            let instr: Instruction = Instruction::from_opcode(self.opcode);
-            self.cycles = instr.get_cycles();
-            self.cycles += instr.execute(self);
+    fn indirect_cycle(&mut self, instr: &Instruction) -> bool {
+        match self.step {
+            2 => {
+                self.temp = self.read_pc() as u16;
+                false
+            }
+            3 => {
+                self.temp |= (self.read_pc() as u16) << 8;
+                false
+            }
+            4 => {
+                self.addr_abs = self.bus.read(self.temp) as u16;
+                false
+            }
+            _ => {
+                // The pointer's high byte is read without carrying into the next page
+                let hi = (self.temp & 0xFF00) | (self.temp.wrapping_add(1) & 0x00FF);
+                self.addr_abs |= (self.bus.read(hi) as u16) << 8;
+                self.operate(instr)
+            }
+        }
+    }
 
-            self.set_flag(CpuFlags::U, true);
+    fn indexed_indirect_cycle(&mut self, instr: &Instruction) -> bool {
+        match self.step {
+            2 => {
+                self.temp = self.read_pc() as u16;
+                false
+            }
+            3 => {
+                self.bus.read(self.temp);
+                self.temp = (self.temp + self.x as u16) & 0x00FF;
+                false
+            }
+            4 => {
+                self.addr_abs = self.bus.read(self.temp) as u16;
+                false
+            }
+            5 => {
+                let hi = self.bus.read((self.temp + 1) & 0x00FF) as u16;
+                self.addr_abs |= hi << 8;
+                false
+            }
+            _ => self.access_cycle(instr, 6),
         }
+    }
 
-        self.clock_count += 1;
+    fn indirect_indexed_cycle(&mut self, instr: &Instruction) -> bool {
+        match self.step {
+            2 => {
+                self.temp = self.read_pc() as u16;
+                false
+            }
+            3 => {
+                self.addr_abs = self.bus.read(self.temp) as u16;
+                false
+            }
+            4 => {
+                let hi = self.bus.read((self.temp + 1) & 0x00FF) as u16;
+                self.add_index((hi << 8) | self.addr_abs, self.y);
+                false
+            }
+            _ => self.index_cycle(instr, 5),
+        }
+    }
 
-        if self.cycles != 0 {
-            self.cycles -= 1;
+    fn branch_cycle(&mut self, instr: &Instruction) -> bool {
+        match self.step {
+            2 => {
+                self.addr_rel = self.read_pc() as i8 as u16;
+                (instr.operator)(self);
+                !self.branch_taken
+            }
+            3 => {
+                // A taken branch doesn't poll on this cycle, an IRQ or NMI that
+                // only showed up on the cycle before waits one more instruction
+                self.skip_poll = true;
+                self.bus.read(self.pc);
+                self.addr_abs = self.pc.wrapping_add(self.addr_rel);
+                if (self.addr_abs & 0xFF00) == (self.pc & 0xFF00) {
+                    self.pc = self.addr_abs;
+                    return true;
+                }
+                // The high byte is fixed up in one more cycle
+                self.pc = (self.pc & 0xFF00) | (self.addr_abs & 0x00FF);
+                false
+            }
+            _ => {
+                self.bus.read(self.pc);
+                self.pc = self.addr_abs;
+                true
+            }
         }
     }
 
-    pub fn clock_ppu(&mut self) {
-        self.bus.ppu.clock()
+    // BRK, IRQ and NMI, only BRK skips its padding byte and pushes the B flag
+    fn interrupt_cycle(&mut self, instr: &Instruction) -> bool {
+        match self.step {
+            2 => {
+                self.bus.read(self.pc);
+                if !self.interrupt {
+                    self.pc = self.pc.wrapping_add(1);
+                }
+                false
+            }
+            3 => {
+                self.push((self.pc >> 8) as u8);
+                false
+            }
+            4 => {
+                self.push((self.pc & 0x00FF) as u8);
+                false
+            }
+            5 => {
+                // B only exists on the stack, PLP can leave it set in the register
+                let b = if self.interrupt { 0x00 } else { CpuFlags::B.0 };
+                self.push((self.status & !CpuFlags::B.0) | CpuFlags::U.0 | b);
+                self.set_flag(CpuFlags::I, true);
+
+                // An NMI that shows up until here hijacks the vector, even of BRK and IRQ
+                self.temp = if self.need_nmi {
+                    self.need_nmi = false;
+                    0xFFFA
+                } else {
+                    0xFFFE
+                };
+                false
+            }
+            6 => {
+                self.addr_abs = self.bus.read(self.temp) as u16;
+                false
+            }
+            _ => {
+                self.addr_abs |= (self.bus.read(self.temp + 1) as u16) << 8;
+                self.interrupt = false;
+                self.operate(instr)
+            }
+        }
+    }
+
+    fn jsr_cycle(&mut self, instr: &Instruction) -> bool {
+        match self.step {
+            2 => {
+                self.addr_abs = self.read_pc() as u16;
+                false
+            }
+            3 => {
+                self.bus
+                    .read(constants::cpu::STACK_BASE_ADDR + (self.stkp as u16));
+                false
+            }
+            4 => {
+                self.push((self.pc >> 8) as u8);
+                false
+            }
+            5 => {
+                self.push((self.pc & 0x00FF) as u8);
+                false
+            }
+            _ => {
+                self.addr_abs |= (self.bus.read(self.pc) as u16) << 8;
+                self.operate(instr)
+            }
+        }
+    }
+
+    fn rti_cycle(&mut self, instr: &Instruction) -> bool {
+        match self.step {
+            2 => {
+                self.bus.read(self.pc);
+                false
+            }
+            3 => {
+                self.bus
+                    .read(constants::cpu::STACK_BASE_ADDR + (self.stkp as u16));
+                false
+            }
+            4 => {
+                // The restored I flag already counts for the poll of this instruction
+                self.status = (self.pull() & !CpuFlags::B.0) | CpuFlags::U.0;
+                false
+            }
+            5 => {
+                self.addr_abs = self.pull() as u16;
+                false
+            }
+            _ => {
+                self.addr_abs |= (self.pull() as u16) << 8;
+                self.operate(instr)
+            }
+        }
+    }
+
+    fn rts_cycle(&mut self, instr: &Instruction) -> bool {
+        match self.step {
+            2 => {
+                self.bus.read(self.pc);
+                false
+            }
+            3 => {
+                self.bus
+                    .read(constants::cpu::STACK_BASE_ADDR + (self.stkp as u16));
+                false
+            }
+            4 => {
+                self.addr_abs = self.pull() as u16;
+                false
+            }
+            5 => {
+                self.addr_abs |= (self.pull() as u16) << 8;
+                false
+            }
+            _ => {
+                self.bus.read(self.addr_abs);
+                self.operate(instr)
+            }
+        }
+    }
+
+    // PHA and PHP, the operator does the push
+    fn push_cycle(&mut self, instr: &Instruction) -> bool {
+        if self.step == 2 {
+            self.bus.read(self.pc);
+            return false;
+        }
+        self.operate(instr)
+    }
+
+    // PLA and PLP, the operator does the pull
+    fn pull_cycle(&mut self, instr: &Instruction) -> bool {
+        match self.step {
+            2 => {
+                self.bus.read(self.pc);
+                false
+            }
+            3 => {
+                self.bus
+                    .read(constants::cpu::STACK_BASE_ADDR + (self.stkp as u16));
+                false
+            }
+            _ => self.operate(instr),
+        }
     }
 }
@@ -1,41 +1,30 @@
 use crate::cpu::cpu::Cpu;
 
-use super::{addrmode::AddrMode, opcodes::Opcode};
+use super::{addrmode::AddrMode, opcodes::INSTRUCTIONS};
+
+// How the instruction uses the memory at its effective address
+#[derive(Clone, Copy, PartialEq)]
+pub enum Access {
+    None,
+    Read,
+    Write,
+    ReadModifyWrite,
+}
 
 pub struct Instruction {
-    name: String,
-    operator: Box<dyn Operation>,
-    addrmode: Box<dyn Operation>,
-    addrtype: AddrMode,
-    cycles: u8,
+    pub name: &'static str,
+    // Runs on the last cycle, once the addressing cycles are done
+    pub operator: fn(&mut Cpu),
+    pub addrtype: AddrMode,
+    // Without page crossings and taken branches
+    pub cycles: u8,
+    pub bytes: u8,
+    pub access: Access,
 }
 
 impl Instruction {
-    pub fn new(
-        name: &str,
-        operator: Box<dyn Operation>,
-        addrmode: Box<dyn Operation>,
-        addrtype: AddrMode,
-        cycles: u8,
-    ) -> Instruction {
-        Instruction {
-            name: name.to_string(),
-            operator,
-            addrmode,
-            addrtype,
-            cycles,
-        }
-    }
-
-    pub fn from_opcode(opcode: u8) -> Instruction {
-        let instr: Instruction = Opcode(opcode).into();
-        instr
-    }
-
-    pub fn execute(&self, cpu: &mut Cpu) -> u8 {
-        let cycle_addr: u8 = self.addrmode.execute(cpu);
-        let cycle_op: u8 = self.operator.execute(cpu);
-        cycle_addr + cycle_op
+    pub fn from_opcode(opcode: u8) -> &'static Instruction {
+        &INSTRUCTIONS[opcode as usize]
     }
 
     pub fn get_cycles(&self) -> u8 {
@@ -43,14 +32,22 @@ impl Instruction {
     }
 
     pub fn get_name(&self) -> &str {
-        &self.name
+        self.name
     }
 
     pub fn get_addrmode(&self) -> AddrMode {
         self.addrtype
     }
+
+    pub fn get_bytes(&self) -> u8 {
+        self.bytes
+    }
+
+    pub fn get_access(&self) -> Access {
+        self.access
+    }
 }
 
 pub trait Operation {
-    fn execute(&self, cpu: &mut Cpu) -> u8;
+    fn execute(cpu: &mut Cpu);
 }
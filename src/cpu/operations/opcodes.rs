@@ -1,278 +1,278 @@
-use super::{addrmode::*, instruction::Instruction, operators::*};
+use super::{
+    addrmode::AddrMode,
+    instruction::{Access, Instruction, Operation},
+    operators::*,
+};
 
 macro_rules! instruction {
-    ($name:literal, $operator:expr, $addrmode:expr, $addrtype:expr, $cycles:literal) => {
-        Instruction::new(
-            $name,
-            Box::new($operator),
-            Box::new($addrmode),
-            $addrtype,
-            $cycles,
-        )
+    ($name:literal, $operator:ident, $addrtype:expr, $cycles:literal, $access:ident) => {
+        Instruction {
+            name: $name,
+            operator: $operator::execute,
+            addrtype: $addrtype,
+            cycles: $cycles,
+            bytes: $addrtype.bytes(),
+            access: Access::$access,
+        }
     };
 }
 
-pub struct Opcode(pub u8);
-impl From<Opcode> for Instruction {
-    fn from(opcode: Opcode) -> Instruction {
-        let Opcode(id) = opcode;
-        match id {
-            000 => instruction!("BRK", BRK, IMM, AddrMode::IMM, 7),
-            001 => instruction!("ORA", ORA, IZX, AddrMode::IZX, 6),
-            002 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            003 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            004 => instruction!("???", NOP, IMP, AddrMode::IMP, 3),
-            005 => instruction!("ORA", ORA, ZP0, AddrMode::ZP0, 3),
-            006 => instruction!("ASL", ASL, ZP0, AddrMode::ZP0, 5),
-            007 => instruction!("???", XXX, IMP, AddrMode::IMP, 5),
-            008 => instruction!("PHP", PHP, IMP, AddrMode::IMP, 3),
-            009 => instruction!("ORA", ORA, IMM, AddrMode::IMM, 2),
-            010 => instruction!("ASL", ASL, IMP, AddrMode::IMP, 2),
-            011 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            012 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            013 => instruction!("ORA", ORA, ABS, AddrMode::ABS, 4),
-            014 => instruction!("ASL", ASL, ABS, AddrMode::ABS, 6),
-            015 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            016 => instruction!("BPL", BPL, REL, AddrMode::REL, 2),
-            017 => instruction!("ORA", ORA, IZY, AddrMode::IZY, 5),
-            018 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            019 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            020 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            021 => instruction!("ORA", ORA, ZPX, AddrMode::ZPX, 4),
-            022 => instruction!("ASL", ASL, ZPX, AddrMode::ZPX, 6),
-            023 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            024 => instruction!("CLC", CLC, IMP, AddrMode::IMP, 2),
-            025 => instruction!("ORA", ORA, ABY, AddrMode::ABY, 4),
-            026 => instruction!("???", NOP, IMP, AddrMode::IMP, 2),
-            027 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-            028 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            029 => instruction!("ORA", ORA, ABX, AddrMode::ABX, 4),
-            030 => instruction!("ASL", ASL, ABX, AddrMode::ABX, 7),
-            031 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-            032 => instruction!("JSR", JSR, ABS, AddrMode::ABS, 6),
-            033 => instruction!("AND", AND, IZX, AddrMode::IZX, 6),
-            034 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            035 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            036 => instruction!("BIT", BIT, ZP0, AddrMode::ZP0, 3),
-            037 => instruction!("AND", AND, ZP0, AddrMode::ZP0, 3),
-            038 => instruction!("ROL", ROL, ZP0, AddrMode::ZP0, 5),
-            039 => instruction!("???", XXX, IMP, AddrMode::IMP, 5),
-            040 => instruction!("PLP", PLP, IMP, AddrMode::IMP, 4),
-            041 => instruction!("AND", AND, IMM, AddrMode::IMM, 2),
-            042 => instruction!("ROL", ROL, IMP, AddrMode::IMP, 2),
-            043 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            044 => instruction!("BIT", BIT, ABS, AddrMode::ABS, 4),
-            045 => instruction!("AND", AND, ABS, AddrMode::ABS, 4),
-            046 => instruction!("ROL", ROL, ABS, AddrMode::ABS, 6),
-            047 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            048 => instruction!("BMI", BMI, REL, AddrMode::REL, 2),
-            049 => instruction!("AND", AND, IZY, AddrMode::IZY, 5),
-            050 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            051 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            052 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            053 => instruction!("AND", AND, ZPX, AddrMode::ZPX, 4),
-            054 => instruction!("ROL", ROL, ZPX, AddrMode::ZPX, 6),
-            055 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            056 => instruction!("SEC", SEC, IMP, AddrMode::IMP, 2),
-            057 => instruction!("AND", AND, ABY, AddrMode::ABY, 4),
-            058 => instruction!("???", NOP, IMP, AddrMode::IMP, 2),
-            059 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-            060 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            061 => instruction!("AND", AND, ABX, AddrMode::ABX, 4),
-            062 => instruction!("ROL", ROL, ABX, AddrMode::ABX, 7),
-            063 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-            064 => instruction!("RTI", RTI, IMP, AddrMode::IMP, 6),
-            065 => instruction!("EOR", EOR, IZX, AddrMode::IZX, 6),
-            066 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            067 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            068 => instruction!("???", NOP, IMP, AddrMode::IMP, 3),
-            069 => instruction!("EOR", EOR, ZP0, AddrMode::ZP0, 3),
-            070 => instruction!("LSR", LSR, ZP0, AddrMode::ZP0, 5),
-            071 => instruction!("???", XXX, IMP, AddrMode::IMP, 5),
-            072 => instruction!("PHA", PHA, IMP, AddrMode::IMP, 3),
-            073 => instruction!("EOR", EOR, IMM, AddrMode::IMM, 2),
-            074 => instruction!("LSR", LSR, IMP, AddrMode::IMP, 2),
-            075 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            076 => instruction!("JMP", JMP, ABS, AddrMode::ABS, 3),
-            077 => instruction!("EOR", EOR, ABS, AddrMode::ABS, 4),
-            078 => instruction!("LSR", LSR, ABS, AddrMode::ABS, 6),
-            079 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            080 => instruction!("BVC", BVC, REL, AddrMode::REL, 2),
-            081 => instruction!("EOR", EOR, IZY, AddrMode::IZY, 5),
-            082 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            083 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            084 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            085 => instruction!("EOR", EOR, ZPX, AddrMode::ZPX, 4),
-            086 => instruction!("LSR", LSR, ZPX, AddrMode::ZPX, 6),
-            087 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            088 => instruction!("CLI", CLI, IMP, AddrMode::IMP, 2),
-            089 => instruction!("EOR", EOR, ABY, AddrMode::ABY, 4),
-            090 => instruction!("???", NOP, IMP, AddrMode::IMP, 2),
-            091 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-            092 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            093 => instruction!("EOR", EOR, ABX, AddrMode::ABX, 4),
-            094 => instruction!("LSR", LSR, ABX, AddrMode::ABX, 7),
-            095 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-            096 => instruction!("RTS", RTS, IMP, AddrMode::IMP, 6),
-            097 => instruction!("ADC", ADC, IZX, AddrMode::IZX, 6),
-            098 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            099 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            100 => instruction!("???", NOP, IMP, AddrMode::IMP, 3),
-            101 => instruction!("ADC", ADC, ZP0, AddrMode::ZP0, 3),
-            102 => instruction!("ROR", ROR, ZP0, AddrMode::ZP0, 5),
-            103 => instruction!("???", XXX, IMP, AddrMode::IMP, 5),
-            104 => instruction!("PLA", PLA, IMP, AddrMode::IMP, 4),
-            105 => instruction!("ADC", ADC, IMM, AddrMode::IMM, 2),
-            106 => instruction!("ROR", ROR, IMP, AddrMode::IMP, 2),
-            107 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            108 => instruction!("JMP", JMP, IND, AddrMode::IND, 5),
-            109 => instruction!("ADC", ADC, ABS, AddrMode::ABS, 4),
-            110 => instruction!("ROR", ROR, ABS, AddrMode::ABS, 6),
-            111 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            112 => instruction!("BVS", BVS, REL, AddrMode::REL, 2),
-            113 => instruction!("ADC", ADC, IZY, AddrMode::IZY, 5),
-            114 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            115 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            116 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            117 => instruction!("ADC", ADC, ZPX, AddrMode::ZPX, 4),
-            118 => instruction!("ROR", ROR, ZPX, AddrMode::ZPX, 6),
-            119 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            120 => instruction!("SEI", SEI, IMP, AddrMode::IMP, 2),
-            121 => instruction!("ADC", ADC, ABY, AddrMode::ABY, 4),
-            122 => instruction!("???", NOP, IMP, AddrMode::IMP, 2),
-            123 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-            124 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            125 => instruction!("ADC", ADC, ABX, AddrMode::ABX, 4),
-            126 => instruction!("ROR", ROR, ABX, AddrMode::ABX, 7),
-            127 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-            128 => instruction!("???", NOP, IMP, AddrMode::IMP, 2),
-            129 => instruction!("STA", STA, IZX, AddrMode::IZX, 6),
-            130 => instruction!("???", NOP, IMP, AddrMode::IMP, 2),
-            131 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            132 => instruction!("STY", STY, ZP0, AddrMode::ZP0, 3),
-            133 => instruction!("STA", STA, ZP0, AddrMode::ZP0, 3),
-            134 => instruction!("STX", STX, ZP0, AddrMode::ZP0, 3),
-            135 => instruction!("???", XXX, IMP, AddrMode::IMP, 3),
-            136 => instruction!("DEY", DEY, IMP, AddrMode::IMP, 2),
-            137 => instruction!("???", NOP, IMP, AddrMode::IMP, 2),
-            138 => instruction!("TXA", TXA, IMP, AddrMode::IMP, 2),
-            139 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            140 => instruction!("STY", STY, ABS, AddrMode::ABS, 4),
-            141 => instruction!("STA", STA, ABS, AddrMode::ABS, 4),
-            142 => instruction!("STX", STX, ABS, AddrMode::ABS, 4),
-            143 => instruction!("???", XXX, IMP, AddrMode::IMP, 4),
-            144 => instruction!("BCC", BCC, REL, AddrMode::REL, 2),
-            145 => instruction!("STA", STA, IZY, AddrMode::IZY, 6),
-            146 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            147 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            148 => instruction!("STY", STY, ZPX, AddrMode::ZPX, 4),
-            149 => instruction!("STA", STA, ZPX, AddrMode::ZPX, 4),
-            150 => instruction!("STX", STX, ZPY, AddrMode::ZPY, 4),
-            151 => instruction!("???", XXX, IMP, AddrMode::IMP, 4),
-            152 => instruction!("TYA", TYA, IMP, AddrMode::IMP, 2),
-            153 => instruction!("STA", STA, ABY, AddrMode::ABY, 5),
-            154 => instruction!("TXS", TXS, IMP, AddrMode::IMP, 2),
-            155 => instruction!("???", XXX, IMP, AddrMode::IMP, 5),
-            156 => instruction!("???", NOP, IMP, AddrMode::IMP, 5),
-            157 => instruction!("STA", STA, ABX, AddrMode::ABX, 5),
-            158 => instruction!("???", XXX, IMP, AddrMode::IMP, 5),
-            159 => instruction!("???", XXX, IMP, AddrMode::IMP, 5),
-            160 => instruction!("LDY", LDY, IMM, AddrMode::IMM, 2),
-            161 => instruction!("LDA", LDA, IZX, AddrMode::IZX, 6),
-            162 => instruction!("LDX", LDX, IMM, AddrMode::IMM, 2),
-            163 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            164 => instruction!("LDY", LDY, ZP0, AddrMode::ZP0, 3),
-            165 => instruction!("LDA", LDA, ZP0, AddrMode::ZP0, 3),
-            166 => instruction!("LDX", LDX, ZP0, AddrMode::ZP0, 3),
-            167 => instruction!("???", XXX, IMP, AddrMode::IMP, 3),
-            168 => instruction!("TAY", TAY, IMP, AddrMode::IMP, 2),
-            169 => instruction!("LDA", LDA, IMM, AddrMode::IMM, 2),
-            170 => instruction!("TAX", TAX, IMP, AddrMode::IMP, 2),
-            171 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            172 => instruction!("LDY", LDY, ABS, AddrMode::ABS, 4),
-            173 => instruction!("LDA", LDA, ABS, AddrMode::ABS, 4),
-            174 => instruction!("LDX", LDX, ABS, AddrMode::ABS, 4),
-            175 => instruction!("???", XXX, IMP, AddrMode::IMP, 4),
-            176 => instruction!("BCS", BCS, REL, AddrMode::REL, 2),
-            177 => instruction!("LDA", LDA, IZY, AddrMode::IZY, 5),
-            178 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            179 => instruction!("???", XXX, IMP, AddrMode::IMP, 5),
-            180 => instruction!("LDY", LDY, ZPX, AddrMode::ZPX, 4),
-            181 => instruction!("LDA", LDA, ZPX, AddrMode::ZPX, 4),
-            182 => instruction!("LDX", LDX, ZPY, AddrMode::ZPY, 4),
-            183 => instruction!("???", XXX, IMP, AddrMode::IMP, 4),
-            184 => instruction!("CLV", CLV, IMP, AddrMode::IMP, 2),
-            185 => instruction!("LDA", LDA, ABY, AddrMode::ABY, 4),
-            186 => instruction!("TSX", TSX, IMP, AddrMode::IMP, 2),
-            187 => instruction!("???", XXX, IMP, AddrMode::IMP, 4),
-            188 => instruction!("LDY", LDY, ABX, AddrMode::ABX, 4),
-            189 => instruction!("LDA", LDA, ABX, AddrMode::ABX, 4),
-            190 => instruction!("LDX", LDX, ABY, AddrMode::ABY, 4),
-            191 => instruction!("???", XXX, IMP, AddrMode::IMP, 4),
-            192 => instruction!("CPY", CPY, IMM, AddrMode::IMM, 2),
-            193 => instruction!("CMP", CMP, IZX, AddrMode::IZX, 6),
-            194 => instruction!("???", NOP, IMP, AddrMode::IMP, 2),
-            195 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            196 => instruction!("CPY", CPY, ZP0, AddrMode::ZP0, 3),
-            197 => instruction!("CMP", CMP, ZP0, AddrMode::ZP0, 3),
-            198 => instruction!("DEC", DEC, ZP0, AddrMode::ZP0, 5),
-            199 => instruction!("???", XXX, IMP, AddrMode::IMP, 5),
-            200 => instruction!("INY", INY, IMP, AddrMode::IMP, 2),
-            201 => instruction!("CMP", CMP, IMM, AddrMode::IMM, 2),
-            202 => instruction!("DEX", DEX, IMP, AddrMode::IMP, 2),
-            203 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            204 => instruction!("CPY", CPY, ABS, AddrMode::ABS, 4),
-            205 => instruction!("CMP", CMP, ABS, AddrMode::ABS, 4),
-            206 => instruction!("DEC", DEC, ABS, AddrMode::ABS, 6),
-            207 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            208 => instruction!("BNE", BNE, REL, AddrMode::REL, 2),
-            209 => instruction!("CMP", CMP, IZY, AddrMode::IZY, 5),
-            210 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            211 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            212 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            213 => instruction!("CMP", CMP, ZPX, AddrMode::ZPX, 4),
-            214 => instruction!("DEC", DEC, ZPX, AddrMode::ZPX, 6),
-            215 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            216 => instruction!("CLD", CLD, IMP, AddrMode::IMP, 2),
-            217 => instruction!("CMP", CMP, ABY, AddrMode::ABY, 4),
-            218 => instruction!("NOP", NOP, IMP, AddrMode::IMP, 2),
-            219 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-            220 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            221 => instruction!("CMP", CMP, ABX, AddrMode::ABX, 4),
-            222 => instruction!("DEC", DEC, ABX, AddrMode::ABX, 7),
-            223 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-            224 => instruction!("CPX", CPX, IMM, AddrMode::IMM, 2),
-            225 => instruction!("SBC", SBC, IZX, AddrMode::IZX, 6),
-            226 => instruction!("???", NOP, IMP, AddrMode::IMP, 2),
-            227 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            228 => instruction!("CPX", CPX, ZP0, AddrMode::ZP0, 3),
-            229 => instruction!("SBC", SBC, ZP0, AddrMode::ZP0, 3),
-            230 => instruction!("INC", INC, ZP0, AddrMode::ZP0, 5),
-            231 => instruction!("???", XXX, IMP, AddrMode::IMP, 5),
-            232 => instruction!("INX", INX, IMP, AddrMode::IMP, 2),
-            233 => instruction!("SBC", SBC, IMM, AddrMode::IMM, 2),
-            234 => instruction!("NOP", NOP, IMP, AddrMode::IMP, 2),
-            235 => instruction!("???", SBC, IMP, AddrMode::IMP, 2),
-            236 => instruction!("CPX", CPX, ABS, AddrMode::ABS, 4),
-            237 => instruction!("SBC", SBC, ABS, AddrMode::ABS, 4),
-            238 => instruction!("INC", INC, ABS, AddrMode::ABS, 6),
-            239 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            240 => instruction!("BEQ", BEQ, REL, AddrMode::REL, 2),
-            241 => instruction!("SBC", SBC, IZY, AddrMode::IZY, 5),
-            242 => instruction!("???", XXX, IMP, AddrMode::IMP, 2),
-            243 => instruction!("???", XXX, IMP, AddrMode::IMP, 8),
-            244 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            245 => instruction!("SBC", SBC, ZPX, AddrMode::ZPX, 4),
-            246 => instruction!("INC", INC, ZPX, AddrMode::ZPX, 6),
-            247 => instruction!("???", XXX, IMP, AddrMode::IMP, 6),
-            248 => instruction!("SED", SED, IMP, AddrMode::IMP, 2),
-            249 => instruction!("SBC", SBC, ABY, AddrMode::ABY, 4),
-            250 => instruction!("NOP", NOP, IMP, AddrMode::IMP, 2),
-            251 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-            252 => instruction!("???", NOP, IMP, AddrMode::IMP, 4),
-            253 => instruction!("SBC", SBC, ABX, AddrMode::ABX, 4),
-            254 => instruction!("INC", INC, ABX, AddrMode::ABX, 7),
-            255 => instruction!("???", XXX, IMP, AddrMode::IMP, 7),
-        }
-    }
-}
+// Indexed by opcode, shared by the CPU and the disassembler
+pub static INSTRUCTIONS: [Instruction; 256] = [
+    instruction!("BRK", BRK, AddrMode::IMM, 7, None), // 0x00
+    instruction!("ORA", ORA, AddrMode::IZX, 6, Read), // 0x01
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0x02
+    instruction!("SLO", SLO, AddrMode::IZX, 8, ReadModifyWrite), // 0x03
+    instruction!("NOP", NOP, AddrMode::ZP0, 3, Read), // 0x04
+    instruction!("ORA", ORA, AddrMode::ZP0, 3, Read), // 0x05
+    instruction!("ASL", ASL, AddrMode::ZP0, 5, ReadModifyWrite), // 0x06
+    instruction!("SLO", SLO, AddrMode::ZP0, 5, ReadModifyWrite), // 0x07
+    instruction!("PHP", PHP, AddrMode::IMP, 3, None), // 0x08
+    instruction!("ORA", ORA, AddrMode::IMM, 2, Read), // 0x09
+    instruction!("ASL", ASL, AddrMode::IMP, 2, None), // 0x0A
+    instruction!("ANC", ANC, AddrMode::IMM, 2, Read), // 0x0B
+    instruction!("NOP", NOP, AddrMode::ABS, 4, Read), // 0x0C
+    instruction!("ORA", ORA, AddrMode::ABS, 4, Read), // 0x0D
+    instruction!("ASL", ASL, AddrMode::ABS, 6, ReadModifyWrite), // 0x0E
+    instruction!("SLO", SLO, AddrMode::ABS, 6, ReadModifyWrite), // 0x0F
+    instruction!("BPL", BPL, AddrMode::REL, 2, None), // 0x10
+    instruction!("ORA", ORA, AddrMode::IZY, 5, Read), // 0x11
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0x12
+    instruction!("SLO", SLO, AddrMode::IZY, 8, ReadModifyWrite), // 0x13
+    instruction!("NOP", NOP, AddrMode::ZPX, 4, Read), // 0x14
+    instruction!("ORA", ORA, AddrMode::ZPX, 4, Read), // 0x15
+    instruction!("ASL", ASL, AddrMode::ZPX, 6, ReadModifyWrite), // 0x16
+    instruction!("SLO", SLO, AddrMode::ZPX, 6, ReadModifyWrite), // 0x17
+    instruction!("CLC", CLC, AddrMode::IMP, 2, None), // 0x18
+    instruction!("ORA", ORA, AddrMode::ABY, 4, Read), // 0x19
+    instruction!("NOP", NOP, AddrMode::IMP, 2, None), // 0x1A
+    instruction!("SLO", SLO, AddrMode::ABY, 7, ReadModifyWrite), // 0x1B
+    instruction!("NOP", NOP, AddrMode::ABX, 4, Read), // 0x1C
+    instruction!("ORA", ORA, AddrMode::ABX, 4, Read), // 0x1D
+    instruction!("ASL", ASL, AddrMode::ABX, 7, ReadModifyWrite), // 0x1E
+    instruction!("SLO", SLO, AddrMode::ABX, 7, ReadModifyWrite), // 0x1F
+    instruction!("JSR", JSR, AddrMode::ABS, 6, None), // 0x20
+    instruction!("AND", AND, AddrMode::IZX, 6, Read), // 0x21
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0x22
+    instruction!("RLA", RLA, AddrMode::IZX, 8, ReadModifyWrite), // 0x23
+    instruction!("BIT", BIT, AddrMode::ZP0, 3, Read), // 0x24
+    instruction!("AND", AND, AddrMode::ZP0, 3, Read), // 0x25
+    instruction!("ROL", ROL, AddrMode::ZP0, 5, ReadModifyWrite), // 0x26
+    instruction!("RLA", RLA, AddrMode::ZP0, 5, ReadModifyWrite), // 0x27
+    instruction!("PLP", PLP, AddrMode::IMP, 4, None), // 0x28
+    instruction!("AND", AND, AddrMode::IMM, 2, Read), // 0x29
+    instruction!("ROL", ROL, AddrMode::IMP, 2, None), // 0x2A
+    instruction!("ANC", ANC, AddrMode::IMM, 2, Read), // 0x2B
+    instruction!("BIT", BIT, AddrMode::ABS, 4, Read), // 0x2C
+    instruction!("AND", AND, AddrMode::ABS, 4, Read), // 0x2D
+    instruction!("ROL", ROL, AddrMode::ABS, 6, ReadModifyWrite), // 0x2E
+    instruction!("RLA", RLA, AddrMode::ABS, 6, ReadModifyWrite), // 0x2F
+    instruction!("BMI", BMI, AddrMode::REL, 2, None), // 0x30
+    instruction!("AND", AND, AddrMode::IZY, 5, Read), // 0x31
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0x32
+    instruction!("RLA", RLA, AddrMode::IZY, 8, ReadModifyWrite), // 0x33
+    instruction!("NOP", NOP, AddrMode::ZPX, 4, Read), // 0x34
+    instruction!("AND", AND, AddrMode::ZPX, 4, Read), // 0x35
+    instruction!("ROL", ROL, AddrMode::ZPX, 6, ReadModifyWrite), // 0x36
+    instruction!("RLA", RLA, AddrMode::ZPX, 6, ReadModifyWrite), // 0x37
+    instruction!("SEC", SEC, AddrMode::IMP, 2, None), // 0x38
+    instruction!("AND", AND, AddrMode::ABY, 4, Read), // 0x39
+    instruction!("NOP", NOP, AddrMode::IMP, 2, None), // 0x3A
+    instruction!("RLA", RLA, AddrMode::ABY, 7, ReadModifyWrite), // 0x3B
+    instruction!("NOP", NOP, AddrMode::ABX, 4, Read), // 0x3C
+    instruction!("AND", AND, AddrMode::ABX, 4, Read), // 0x3D
+    instruction!("ROL", ROL, AddrMode::ABX, 7, ReadModifyWrite), // 0x3E
+    instruction!("RLA", RLA, AddrMode::ABX, 7, ReadModifyWrite), // 0x3F
+    instruction!("RTI", RTI, AddrMode::IMP, 6, None), // 0x40
+    instruction!("EOR", EOR, AddrMode::IZX, 6, Read), // 0x41
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0x42
+    instruction!("SRE", SRE, AddrMode::IZX, 8, ReadModifyWrite), // 0x43
+    instruction!("NOP", NOP, AddrMode::ZP0, 3, Read), // 0x44
+    instruction!("EOR", EOR, AddrMode::ZP0, 3, Read), // 0x45
+    instruction!("LSR", LSR, AddrMode::ZP0, 5, ReadModifyWrite), // 0x46
+    instruction!("SRE", SRE, AddrMode::ZP0, 5, ReadModifyWrite), // 0x47
+    instruction!("PHA", PHA, AddrMode::IMP, 3, None), // 0x48
+    instruction!("EOR", EOR, AddrMode::IMM, 2, Read), // 0x49
+    instruction!("LSR", LSR, AddrMode::IMP, 2, None), // 0x4A
+    instruction!("ALR", ALR, AddrMode::IMM, 2, Read), // 0x4B
+    instruction!("JMP", JMP, AddrMode::ABS, 3, None), // 0x4C
+    instruction!("EOR", EOR, AddrMode::ABS, 4, Read), // 0x4D
+    instruction!("LSR", LSR, AddrMode::ABS, 6, ReadModifyWrite), // 0x4E
+    instruction!("SRE", SRE, AddrMode::ABS, 6, ReadModifyWrite), // 0x4F
+    instruction!("BVC", BVC, AddrMode::REL, 2, None), // 0x50
+    instruction!("EOR", EOR, AddrMode::IZY, 5, Read), // 0x51
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0x52
+    instruction!("SRE", SRE, AddrMode::IZY, 8, ReadModifyWrite), // 0x53
+    instruction!("NOP", NOP, AddrMode::ZPX, 4, Read), // 0x54
+    instruction!("EOR", EOR, AddrMode::ZPX, 4, Read), // 0x55
+    instruction!("LSR", LSR, AddrMode::ZPX, 6, ReadModifyWrite), // 0x56
+    instruction!("SRE", SRE, AddrMode::ZPX, 6, ReadModifyWrite), // 0x57
+    instruction!("CLI", CLI, AddrMode::IMP, 2, None), // 0x58
+    instruction!("EOR", EOR, AddrMode::ABY, 4, Read), // 0x59
+    instruction!("NOP", NOP, AddrMode::IMP, 2, None), // 0x5A
+    instruction!("SRE", SRE, AddrMode::ABY, 7, ReadModifyWrite), // 0x5B
+    instruction!("NOP", NOP, AddrMode::ABX, 4, Read), // 0x5C
+    instruction!("EOR", EOR, AddrMode::ABX, 4, Read), // 0x5D
+    instruction!("LSR", LSR, AddrMode::ABX, 7, ReadModifyWrite), // 0x5E
+    instruction!("SRE", SRE, AddrMode::ABX, 7, ReadModifyWrite), // 0x5F
+    instruction!("RTS", RTS, AddrMode::IMP, 6, None), // 0x60
+    instruction!("ADC", ADC, AddrMode::IZX, 6, Read), // 0x61
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0x62
+    instruction!("RRA", RRA, AddrMode::IZX, 8, ReadModifyWrite), // 0x63
+    instruction!("NOP", NOP, AddrMode::ZP0, 3, Read), // 0x64
+    instruction!("ADC", ADC, AddrMode::ZP0, 3, Read), // 0x65
+    instruction!("ROR", ROR, AddrMode::ZP0, 5, ReadModifyWrite), // 0x66
+    instruction!("RRA", RRA, AddrMode::ZP0, 5, ReadModifyWrite), // 0x67
+    instruction!("PLA", PLA, AddrMode::IMP, 4, None), // 0x68
+    instruction!("ADC", ADC, AddrMode::IMM, 2, Read), // 0x69
+    instruction!("ROR", ROR, AddrMode::IMP, 2, None), // 0x6A
+    instruction!("ARR", ARR, AddrMode::IMM, 2, Read), // 0x6B
+    instruction!("JMP", JMP, AddrMode::IND, 5, None), // 0x6C
+    instruction!("ADC", ADC, AddrMode::ABS, 4, Read), // 0x6D
+    instruction!("ROR", ROR, AddrMode::ABS, 6, ReadModifyWrite), // 0x6E
+    instruction!("RRA", RRA, AddrMode::ABS, 6, ReadModifyWrite), // 0x6F
+    instruction!("BVS", BVS, AddrMode::REL, 2, None), // 0x70
+    instruction!("ADC", ADC, AddrMode::IZY, 5, Read), // 0x71
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0x72
+    instruction!("RRA", RRA, AddrMode::IZY, 8, ReadModifyWrite), // 0x73
+    instruction!("NOP", NOP, AddrMode::ZPX, 4, Read), // 0x74
+    instruction!("ADC", ADC, AddrMode::ZPX, 4, Read), // 0x75
+    instruction!("ROR", ROR, AddrMode::ZPX, 6, ReadModifyWrite), // 0x76
+    instruction!("RRA", RRA, AddrMode::ZPX, 6, ReadModifyWrite), // 0x77
+    instruction!("SEI", SEI, AddrMode::IMP, 2, None), // 0x78
+    instruction!("ADC", ADC, AddrMode::ABY, 4, Read), // 0x79
+    instruction!("NOP", NOP, AddrMode::IMP, 2, None), // 0x7A
+    instruction!("RRA", RRA, AddrMode::ABY, 7, ReadModifyWrite), // 0x7B
+    instruction!("NOP", NOP, AddrMode::ABX, 4, Read), // 0x7C
+    instruction!("ADC", ADC, AddrMode::ABX, 4, Read), // 0x7D
+    instruction!("ROR", ROR, AddrMode::ABX, 7, ReadModifyWrite), // 0x7E
+    instruction!("RRA", RRA, AddrMode::ABX, 7, ReadModifyWrite), // 0x7F
+    instruction!("NOP", NOP, AddrMode::IMM, 2, Read), // 0x80
+    instruction!("STA", STA, AddrMode::IZX, 6, Write), // 0x81
+    instruction!("NOP", NOP, AddrMode::IMM, 2, Read), // 0x82
+    instruction!("SAX", SAX, AddrMode::IZX, 6, Write), // 0x83
+    instruction!("STY", STY, AddrMode::ZP0, 3, Write), // 0x84
+    instruction!("STA", STA, AddrMode::ZP0, 3, Write), // 0x85
+    instruction!("STX", STX, AddrMode::ZP0, 3, Write), // 0x86
+    instruction!("SAX", SAX, AddrMode::ZP0, 3, Write), // 0x87
+    instruction!("DEY", DEY, AddrMode::IMP, 2, None), // 0x88
+    instruction!("NOP", NOP, AddrMode::IMM, 2, Read), // 0x89
+    instruction!("TXA", TXA, AddrMode::IMP, 2, None), // 0x8A
+    instruction!("XAA", XAA, AddrMode::IMM, 2, Read), // 0x8B
+    instruction!("STY", STY, AddrMode::ABS, 4, Write), // 0x8C
+    instruction!("STA", STA, AddrMode::ABS, 4, Write), // 0x8D
+    instruction!("STX", STX, AddrMode::ABS, 4, Write), // 0x8E
+    instruction!("SAX", SAX, AddrMode::ABS, 4, Write), // 0x8F
+    instruction!("BCC", BCC, AddrMode::REL, 2, None), // 0x90
+    instruction!("STA", STA, AddrMode::IZY, 6, Write), // 0x91
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0x92
+    instruction!("SHA", SHA, AddrMode::IZY, 6, Write), // 0x93
+    instruction!("STY", STY, AddrMode::ZPX, 4, Write), // 0x94
+    instruction!("STA", STA, AddrMode::ZPX, 4, Write), // 0x95
+    instruction!("STX", STX, AddrMode::ZPY, 4, Write), // 0x96
+    instruction!("SAX", SAX, AddrMode::ZPY, 4, Write), // 0x97
+    instruction!("TYA", TYA, AddrMode::IMP, 2, None), // 0x98
+    instruction!("STA", STA, AddrMode::ABY, 5, Write), // 0x99
+    instruction!("TXS", TXS, AddrMode::IMP, 2, None), // 0x9A
+    instruction!("TAS", TAS, AddrMode::ABY, 5, Write), // 0x9B
+    instruction!("SHY", SHY, AddrMode::ABX, 5, Write), // 0x9C
+    instruction!("STA", STA, AddrMode::ABX, 5, Write), // 0x9D
+    instruction!("SHX", SHX, AddrMode::ABY, 5, Write), // 0x9E
+    instruction!("SHA", SHA, AddrMode::ABY, 5, Write), // 0x9F
+    instruction!("LDY", LDY, AddrMode::IMM, 2, Read), // 0xA0
+    instruction!("LDA", LDA, AddrMode::IZX, 6, Read), // 0xA1
+    instruction!("LDX", LDX, AddrMode::IMM, 2, Read), // 0xA2
+    instruction!("LAX", LAX, AddrMode::IZX, 6, Read), // 0xA3
+    instruction!("LDY", LDY, AddrMode::ZP0, 3, Read), // 0xA4
+    instruction!("LDA", LDA, AddrMode::ZP0, 3, Read), // 0xA5
+    instruction!("LDX", LDX, AddrMode::ZP0, 3, Read), // 0xA6
+    instruction!("LAX", LAX, AddrMode::ZP0, 3, Read), // 0xA7
+    instruction!("TAY", TAY, AddrMode::IMP, 2, None), // 0xA8
+    instruction!("LDA", LDA, AddrMode::IMM, 2, Read), // 0xA9
+    instruction!("TAX", TAX, AddrMode::IMP, 2, None), // 0xAA
+    instruction!("LXA", LXA, AddrMode::IMM, 2, Read), // 0xAB
+    instruction!("LDY", LDY, AddrMode::ABS, 4, Read), // 0xAC
+    instruction!("LDA", LDA, AddrMode::ABS, 4, Read), // 0xAD
+    instruction!("LDX", LDX, AddrMode::ABS, 4, Read), // 0xAE
+    instruction!("LAX", LAX, AddrMode::ABS, 4, Read), // 0xAF
+    instruction!("BCS", BCS, AddrMode::REL, 2, None), // 0xB0
+    instruction!("LDA", LDA, AddrMode::IZY, 5, Read), // 0xB1
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0xB2
+    instruction!("LAX", LAX, AddrMode::IZY, 5, Read), // 0xB3
+    instruction!("LDY", LDY, AddrMode::ZPX, 4, Read), // 0xB4
+    instruction!("LDA", LDA, AddrMode::ZPX, 4, Read), // 0xB5
+    instruction!("LDX", LDX, AddrMode::ZPY, 4, Read), // 0xB6
+    instruction!("LAX", LAX, AddrMode::ZPY, 4, Read), // 0xB7
+    instruction!("CLV", CLV, AddrMode::IMP, 2, None), // 0xB8
+    instruction!("LDA", LDA, AddrMode::ABY, 4, Read), // 0xB9
+    instruction!("TSX", TSX, AddrMode::IMP, 2, None), // 0xBA
+    instruction!("LAS", LAS, AddrMode::ABY, 4, Read), // 0xBB
+    instruction!("LDY", LDY, AddrMode::ABX, 4, Read), // 0xBC
+    instruction!("LDA", LDA, AddrMode::ABX, 4, Read), // 0xBD
+    instruction!("LDX", LDX, AddrMode::ABY, 4, Read), // 0xBE
+    instruction!("LAX", LAX, AddrMode::ABY, 4, Read), // 0xBF
+    instruction!("CPY", CPY, AddrMode::IMM, 2, Read), // 0xC0
+    instruction!("CMP", CMP, AddrMode::IZX, 6, Read), // 0xC1
+    instruction!("NOP", NOP, AddrMode::IMM, 2, Read), // 0xC2
+    instruction!("DCP", DCP, AddrMode::IZX, 8, ReadModifyWrite), // 0xC3
+    instruction!("CPY", CPY, AddrMode::ZP0, 3, Read), // 0xC4
+    instruction!("CMP", CMP, AddrMode::ZP0, 3, Read), // 0xC5
+    instruction!("DEC", DEC, AddrMode::ZP0, 5, ReadModifyWrite), // 0xC6
+    instruction!("DCP", DCP, AddrMode::ZP0, 5, ReadModifyWrite), // 0xC7
+    instruction!("INY", INY, AddrMode::IMP, 2, None), // 0xC8
+    instruction!("CMP", CMP, AddrMode::IMM, 2, Read), // 0xC9
+    instruction!("DEX", DEX, AddrMode::IMP, 2, None), // 0xCA
+    instruction!("AXS", AXS, AddrMode::IMM, 2, Read), // 0xCB
+    instruction!("CPY", CPY, AddrMode::ABS, 4, Read), // 0xCC
+    instruction!("CMP", CMP, AddrMode::ABS, 4, Read), // 0xCD
+    instruction!("DEC", DEC, AddrMode::ABS, 6, ReadModifyWrite), // 0xCE
+    instruction!("DCP", DCP, AddrMode::ABS, 6, ReadModifyWrite), // 0xCF
+    instruction!("BNE", BNE, AddrMode::REL, 2, None), // 0xD0
+    instruction!("CMP", CMP, AddrMode::IZY, 5, Read), // 0xD1
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0xD2
+    instruction!("DCP", DCP, AddrMode::IZY, 8, ReadModifyWrite), // 0xD3
+    instruction!("NOP", NOP, AddrMode::ZPX, 4, Read), // 0xD4
+    instruction!("CMP", CMP, AddrMode::ZPX, 4, Read), // 0xD5
+    instruction!("DEC", DEC, AddrMode::ZPX, 6, ReadModifyWrite), // 0xD6
+    instruction!("DCP", DCP, AddrMode::ZPX, 6, ReadModifyWrite), // 0xD7
+    instruction!("CLD", CLD, AddrMode::IMP, 2, None), // 0xD8
+    instruction!("CMP", CMP, AddrMode::ABY, 4, Read), // 0xD9
+    instruction!("NOP", NOP, AddrMode::IMP, 2, None), // 0xDA
+    instruction!("DCP", DCP, AddrMode::ABY, 7, ReadModifyWrite), // 0xDB
+    instruction!("NOP", NOP, AddrMode::ABX, 4, Read), // 0xDC
+    instruction!("CMP", CMP, AddrMode::ABX, 4, Read), // 0xDD
+    instruction!("DEC", DEC, AddrMode::ABX, 7, ReadModifyWrite), // 0xDE
+    instruction!("DCP", DCP, AddrMode::ABX, 7, ReadModifyWrite), // 0xDF
+    instruction!("CPX", CPX, AddrMode::IMM, 2, Read), // 0xE0
+    instruction!("SBC", SBC, AddrMode::IZX, 6, Read), // 0xE1
+    instruction!("NOP", NOP, AddrMode::IMM, 2, Read), // 0xE2
+    instruction!("ISC", ISC, AddrMode::IZX, 8, ReadModifyWrite), // 0xE3
+    instruction!("CPX", CPX, AddrMode::ZP0, 3, Read), // 0xE4
+    instruction!("SBC", SBC, AddrMode::ZP0, 3, Read), // 0xE5
+    instruction!("INC", INC, AddrMode::ZP0, 5, ReadModifyWrite), // 0xE6
+    instruction!("ISC", ISC, AddrMode::ZP0, 5, ReadModifyWrite), // 0xE7
+    instruction!("INX", INX, AddrMode::IMP, 2, None), // 0xE8
+    instruction!("SBC", SBC, AddrMode::IMM, 2, Read), // 0xE9
+    instruction!("NOP", NOP, AddrMode::IMP, 2, None), // 0xEA
+    instruction!("SBC", SBC, AddrMode::IMM, 2, Read), // 0xEB
+    instruction!("CPX", CPX, AddrMode::ABS, 4, Read), // 0xEC
+    instruction!("SBC", SBC, AddrMode::ABS, 4, Read), // 0xED
+    instruction!("INC", INC, AddrMode::ABS, 6, ReadModifyWrite), // 0xEE
+    instruction!("ISC", ISC, AddrMode::ABS, 6, ReadModifyWrite), // 0xEF
+    instruction!("BEQ", BEQ, AddrMode::REL, 2, None), // 0xF0
+    instruction!("SBC", SBC, AddrMode::IZY, 5, Read), // 0xF1
+    instruction!("JAM", JAM, AddrMode::IMP, 2, None), // 0xF2
+    instruction!("ISC", ISC, AddrMode::IZY, 8, ReadModifyWrite), // 0xF3
+    instruction!("NOP", NOP, AddrMode::ZPX, 4, Read), // 0xF4
+    instruction!("SBC", SBC, AddrMode::ZPX, 4, Read), // 0xF5
+    instruction!("INC", INC, AddrMode::ZPX, 6, ReadModifyWrite), // 0xF6
+    instruction!("ISC", ISC, AddrMode::ZPX, 6, ReadModifyWrite), // 0xF7
+    instruction!("SED", SED, AddrMode::IMP, 2, None), // 0xF8
+    instruction!("SBC", SBC, AddrMode::ABY, 4, Read), // 0xF9
+    instruction!("NOP", NOP, AddrMode::IMP, 2, None), // 0xFA
+    instruction!("ISC", ISC, AddrMode::ABY, 7, ReadModifyWrite), // 0xFB
+    instruction!("NOP", NOP, AddrMode::ABX, 4, Read), // 0xFC
+    instruction!("SBC", SBC, AddrMode::ABX, 4, Read), // 0xFD
+    instruction!("INC", INC, AddrMode::ABX, 7, ReadModifyWrite), // 0xFE
+    instruction!("ISC", ISC, AddrMode::ABX, 7, ReadModifyWrite), // 0xFF
+];
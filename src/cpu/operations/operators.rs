@@ -5,65 +5,77 @@ use crate::cpu::cpu_flags::CpuFlags;
 use super::addrmode::AddrMode;
 use super::instruction::{Instruction, Operation};
 
-// Operators
-pub struct ADC;
-impl Operation for ADC {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        cpu.fetch();
+// Shared by the official and unofficial arithmetic operators
+fn add_with_carry(cpu: &mut Cpu, value: u8) {
+    cpu.temp = cpu.a as u16 + value as u16 + cpu.get_flag(CpuFlags::C) as u16;
 
-        cpu.temp = cpu.a as u16 + cpu.fetched as u16 + cpu.get_flag(CpuFlags::C) as u16;
+    cpu.set_flag(CpuFlags::C, cpu.temp > 255);
+    cpu.set_flag(CpuFlags::Z, (cpu.temp & 0x00FF) == 0);
+    cpu.set_flag(
+        CpuFlags::V,
+        ((cpu.temp ^ cpu.a as u16) & (cpu.temp ^ value as u16) & 0x0080) != 0,
+    );
+    cpu.set_flag(CpuFlags::N, (cpu.temp & 0x0080) != 0);
 
-        cpu.set_flag(CpuFlags::C, cpu.temp > 255);
-        cpu.set_flag(CpuFlags::Z, (cpu.temp & 0x00FF) == 0);
-        cpu.set_flag(
-            CpuFlags::V,
-            (!(cpu.a as u16 ^ cpu.fetched as u16) & (cpu.a as u16 ^ cpu.temp as u16)) != 0,
-        );
-        cpu.set_flag(CpuFlags::N, (cpu.temp & 0x80) != 0);
+    cpu.a = (cpu.temp & 0x00FF) as u8;
+}
 
-        cpu.a = (cpu.temp & 0x00FF) as u8;
+fn compare(cpu: &mut Cpu, register: u8, value: u8) {
+    let result = register.wrapping_sub(value);
+    cpu.set_flag(CpuFlags::C, register >= value);
+    cpu.set_flag(CpuFlags::Z, result == 0x00);
+    cpu.set_flag(CpuFlags::N, (result & 0x80) != 0);
+}
+
+fn set_zero_negative(cpu: &mut Cpu, value: u8) {
+    cpu.set_flag(CpuFlags::Z, value == 0x00);
+    cpu.set_flag(CpuFlags::N, (value & 0x80) != 0);
+}
 
-        1
+// SHA, SHX, SHY and TAS store the value ANDed with the high byte of the base address plus one,
+// when the index crosses a page that value also replaces the high byte of the target address
+fn store_high_and(cpu: &mut Cpu, value: u8, index: u8) {
+    let base = cpu.addr_abs.wrapping_sub(index as u16);
+    let data = value & ((base >> 8) as u8).wrapping_add(1);
+    let addr = if (base & 0xFF00) != (cpu.addr_abs & 0xFF00) {
+        ((data as u16) << 8) | (cpu.addr_abs & 0x00FF)
+    } else {
+        cpu.addr_abs
+    };
+    cpu.bus.write(addr, data);
+}
+
+// Operators
+pub struct ADC;
+impl Operation for ADC {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        add_with_carry(cpu, cpu.fetched);
     }
 }
 
 pub struct SBC;
 impl Operation for SBC {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
-
-        let value: u16 = (cpu.fetched as u16) ^ 0x00FF;
-        cpu.temp = cpu.a as u16 + value + cpu.get_flag(CpuFlags::C) as u16;
-
-        cpu.set_flag(CpuFlags::C, (cpu.temp & 0xFF00) != 0);
-        cpu.set_flag(CpuFlags::Z, (cpu.temp & 0x00FF) == 0);
-        cpu.set_flag(
-            CpuFlags::V,
-            ((cpu.temp ^ cpu.a as u16) & (cpu.temp ^ value) & 0x0080) != 0,
-        );
-        cpu.set_flag(CpuFlags::N, (cpu.temp & 0x0080) != 0);
-
-        cpu.a = (cpu.temp & 0x00FF) as u8;
-
-        1
+        add_with_carry(cpu, cpu.fetched ^ 0xFF);
     }
 }
 
 pub struct AND;
 impl Operation for AND {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
         cpu.a = cpu.a & cpu.fetched;
 
         cpu.set_flag(CpuFlags::Z, cpu.a == 0);
         cpu.set_flag(CpuFlags::N, (cpu.a & 0x80) != 0);
-        1
     }
 }
 
 pub struct ASL;
 impl Operation for ASL {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
         cpu.temp = (cpu.fetched as u16) << 1;
 
@@ -78,70 +90,33 @@ impl Operation for ASL {
         } else {
             cpu.bus.write(cpu.addr_abs, (cpu.temp & 0x00FF) as u8);
         }
-        0
     }
 }
 
 pub struct BCC;
 impl Operation for BCC {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        if cpu.get_flag(CpuFlags::C) == 0 {
-            cpu.cycles += 1; // Maybe move to return value
-
-            cpu.addr_abs = cpu.pc + cpu.addr_rel;
-
-            if (cpu.addr_abs & 0xFF00) != (cpu.pc & 0xFF00) {
-                cpu.cycles += 1;
-            }
-
-            cpu.pc = cpu.addr_abs
-        }
-
-        0
+    fn execute(cpu: &mut Cpu) {
+        cpu.branch_taken = cpu.get_flag(CpuFlags::C) == 0;
     }
 }
 
 pub struct BCS;
 impl Operation for BCS {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        if cpu.get_flag(CpuFlags::C) == 1 {
-            cpu.cycles += 1; // Maybe move to return value
-
-            cpu.addr_abs = cpu.pc + cpu.addr_rel;
-
-            if (cpu.addr_abs & 0xFF00) != (cpu.pc & 0xFF00) {
-                cpu.cycles += 1;
-            }
-
-            cpu.pc = cpu.addr_abs
-        }
-
-        0
+    fn execute(cpu: &mut Cpu) {
+        cpu.branch_taken = cpu.get_flag(CpuFlags::C) == 1;
     }
 }
 
 pub struct BEQ;
 impl Operation for BEQ {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        if cpu.get_flag(CpuFlags::Z) == 1 {
-            cpu.cycles += 1; // Maybe move to return value
-
-            cpu.addr_abs = cpu.pc + cpu.addr_rel;
-
-            if (cpu.addr_abs & 0xFF00) != (cpu.pc & 0xFF00) {
-                cpu.cycles += 1;
-            }
-
-            cpu.pc = cpu.addr_abs
-        }
-
-        0
+    fn execute(cpu: &mut Cpu) {
+        cpu.branch_taken = cpu.get_flag(CpuFlags::Z) == 1;
     }
 }
 
 pub struct BIT;
 impl Operation for BIT {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
 
         cpu.temp = (cpu.a & cpu.fetched) as u16;
@@ -149,357 +124,237 @@ impl Operation for BIT {
         cpu.set_flag(CpuFlags::Z, (cpu.temp & 0x00FF) == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.fetched & (1 << 7)) != 0);
         cpu.set_flag(CpuFlags::V, (cpu.fetched & (1 << 6)) != 0);
-
-        0
     }
 }
 
 pub struct BMI;
 impl Operation for BMI {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        if cpu.get_flag(CpuFlags::N) == 1 {
-            cpu.cycles += 1; // Maybe move to return value
-
-            cpu.addr_abs = cpu.pc + cpu.addr_rel;
-
-            if (cpu.addr_abs & 0xFF00) != (cpu.pc & 0xFF00) {
-                cpu.cycles += 1;
-            }
-
-            cpu.pc = cpu.addr_abs
-        }
-
-        0
+    fn execute(cpu: &mut Cpu) {
+        cpu.branch_taken = cpu.get_flag(CpuFlags::N) == 1;
     }
 }
 
 pub struct BNE;
 impl Operation for BNE {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        if cpu.get_flag(CpuFlags::Z) == 0 {
-            cpu.cycles += 1; // Maybe move to return value
-
-            cpu.addr_abs = cpu.pc + cpu.addr_rel;
-
-            if (cpu.addr_abs & 0xFF00) != (cpu.pc & 0xFF00) {
-                cpu.cycles += 1;
-            }
-
-            cpu.pc = cpu.addr_abs
-        }
-
-        0
+    fn execute(cpu: &mut Cpu) {
+        cpu.branch_taken = cpu.get_flag(CpuFlags::Z) == 0;
     }
 }
 
 pub struct BPL;
 impl Operation for BPL {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        if cpu.get_flag(CpuFlags::N) == 0 {
-            cpu.cycles += 1; // Maybe move to return value
-
-            cpu.addr_abs = cpu.pc + cpu.addr_rel;
-
-            if (cpu.addr_abs & 0xFF00) != (cpu.pc & 0xFF00) {
-                cpu.cycles += 1;
-            }
-
-            cpu.pc = cpu.addr_abs
-        }
-
-        0
+    fn execute(cpu: &mut Cpu) {
+        cpu.branch_taken = cpu.get_flag(CpuFlags::N) == 0;
     }
 }
 
+// The pushes and the vector fetch happen in the cycles before, shared with IRQ and NMI
 pub struct BRK;
 impl Operation for BRK {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        cpu.pc += 1;
-
-        cpu.set_flag(CpuFlags::I, true);
-        cpu.bus.write(
-            constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16),
-            ((cpu.pc >> 8) & 0x00FF) as u8,
-        );
-        cpu.stkp -= 1;
-        cpu.bus.write(
-            constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16),
-            (cpu.pc & 0x00FF) as u8,
-        );
-        cpu.stkp -= 1;
-
-        cpu.set_flag(CpuFlags::B, true);
-        cpu.bus.write(
-            constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16),
-            cpu.status,
-        );
-        cpu.stkp -= 1;
-        cpu.set_flag(CpuFlags::B, false);
-
-        cpu.pc = cpu.bus.read(0xFFFE) as u16 | ((cpu.bus.read(0xFFFF) as u16) << 8);
-        0
+    fn execute(cpu: &mut Cpu) {
+        cpu.pc = cpu.addr_abs;
     }
 }
 
 pub struct BVC;
 impl Operation for BVC {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        if cpu.get_flag(CpuFlags::V) == 0 {
-            cpu.cycles += 1; // Maybe move to return value
-
-            cpu.addr_abs = cpu.pc + cpu.addr_rel;
-
-            if (cpu.addr_abs & 0xFF00) != (cpu.pc & 0xFF00) {
-                cpu.cycles += 1;
-            }
-
-            cpu.pc = cpu.addr_abs
-        }
-
-        0
+    fn execute(cpu: &mut Cpu) {
+        cpu.branch_taken = cpu.get_flag(CpuFlags::V) == 0;
     }
 }
 
 pub struct BVS;
 impl Operation for BVS {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        if cpu.get_flag(CpuFlags::V) == 1 {
-            cpu.cycles += 1; // Maybe move to return value
-
-            cpu.addr_abs = cpu.pc + cpu.addr_rel;
-
-            if (cpu.addr_abs & 0xFF00) != (cpu.pc & 0xFF00) {
-                cpu.cycles += 1;
-            }
-
-            cpu.pc = cpu.addr_abs
-        }
-
-        0
+    fn execute(cpu: &mut Cpu) {
+        cpu.branch_taken = cpu.get_flag(CpuFlags::V) == 1;
     }
 }
 
 pub struct CLC;
 impl Operation for CLC {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.set_flag(CpuFlags::C, false);
-        0
     }
 }
 
 pub struct CLD;
 impl Operation for CLD {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.set_flag(CpuFlags::D, false);
-        0
     }
 }
 
 pub struct CLI;
 impl Operation for CLI {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.set_flag(CpuFlags::I, false);
-        0
     }
 }
 
 pub struct CLV;
 impl Operation for CLV {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.set_flag(CpuFlags::V, false);
-        0
     }
 }
 
 pub struct CMP;
 impl Operation for CMP {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
         cpu.temp = cpu.a as u16 - cpu.fetched as u16;
 
         cpu.set_flag(CpuFlags::C, cpu.a >= cpu.fetched);
         cpu.set_flag(CpuFlags::Z, (cpu.temp & 0x00FF) == 0x0000);
         cpu.set_flag(CpuFlags::N, (cpu.temp & 0x0080) != 0);
-        1
     }
 }
 
 pub struct CPX;
 impl Operation for CPX {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
         cpu.temp = cpu.x as u16 - cpu.fetched as u16;
 
         cpu.set_flag(CpuFlags::C, cpu.x >= cpu.fetched);
         cpu.set_flag(CpuFlags::Z, (cpu.temp & 0x00FF) == 0x0000);
         cpu.set_flag(CpuFlags::N, (cpu.temp & 0x0080) != 0);
-        0
     }
 }
 
 pub struct CPY;
 impl Operation for CPY {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
         cpu.temp = cpu.y as u16 - cpu.fetched as u16;
 
         cpu.set_flag(CpuFlags::C, cpu.y >= cpu.fetched);
         cpu.set_flag(CpuFlags::Z, (cpu.temp & 0x00FF) == 0x0000);
         cpu.set_flag(CpuFlags::N, (cpu.temp & 0x0080) != 0);
-        0
     }
 }
 
 pub struct DEC;
 impl Operation for DEC {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
         cpu.temp = (cpu.fetched - 1) as u16;
         cpu.bus.write(cpu.addr_abs, (cpu.temp & 0x00FF) as u8);
         cpu.set_flag(CpuFlags::Z, (cpu.temp & 0x00FF) == 0x0000);
         cpu.set_flag(CpuFlags::N, (cpu.temp & 0x0080) != 0);
-        0
     }
 }
 
 pub struct DEX;
 impl Operation for DEX {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.x -= 1;
         cpu.set_flag(CpuFlags::Z, cpu.x == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.x & 0x80) != 0);
-        0
     }
 }
 
 pub struct DEY;
 impl Operation for DEY {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.y -= 1;
         cpu.set_flag(CpuFlags::Z, cpu.y == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.y & 0x80) != 0);
-        0
     }
 }
 
 pub struct EOR;
 impl Operation for EOR {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
         cpu.a = cpu.a ^ cpu.fetched;
 
         cpu.set_flag(CpuFlags::Z, cpu.a == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.a & 0x80) != 0);
-
-        1
     }
 }
 
 pub struct INC;
 impl Operation for INC {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
 
         cpu.temp = (cpu.fetched as u16) + 1;
         cpu.bus.write(cpu.addr_abs, (cpu.temp & 0x00FF) as u8);
         cpu.set_flag(CpuFlags::Z, (cpu.temp & 0x00FF) == 0x0000);
         cpu.set_flag(CpuFlags::N, (cpu.temp & 0x0080) != 0);
-
-        0
     }
 }
 
 pub struct INX;
 impl Operation for INX {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.x += 1;
         cpu.set_flag(CpuFlags::Z, cpu.x == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.x & 0x80) != 0);
-
-        0
     }
 }
 
 pub struct INY;
 impl Operation for INY {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.y += 1;
         cpu.set_flag(CpuFlags::Z, cpu.y == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.y & 0x80) != 0);
-
-        0
     }
 }
 
 pub struct JMP;
 impl Operation for JMP {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.pc = cpu.addr_abs;
-        0
     }
 }
 
+// The return address is pushed in the cycles before
 pub struct JSR;
 impl Operation for JSR {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        cpu.pc -= 1;
-
-        cpu.bus.write(
-            constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16),
-            ((cpu.pc >> 8) & 0x00FF) as u8,
-        );
-        cpu.stkp -= 1;
-
-        cpu.bus.write(
-            constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16),
-            (cpu.pc & 0x00FF) as u8,
-        );
-        cpu.stkp -= 1;
-
+    fn execute(cpu: &mut Cpu) {
         cpu.pc = cpu.addr_abs;
-        0
     }
 }
 
 pub struct LDA;
 impl Operation for LDA {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
 
         cpu.a = cpu.fetched;
         cpu.set_flag(CpuFlags::Z, cpu.a == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.a & 0x80) != 0);
-        1
     }
 }
 
 pub struct LDX;
 impl Operation for LDX {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
 
         cpu.x = cpu.fetched;
         cpu.set_flag(CpuFlags::Z, cpu.x == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.x & 0x80) != 0);
-        1
     }
 }
 
 pub struct LDY;
 impl Operation for LDY {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
 
         cpu.y = cpu.fetched;
         cpu.set_flag(CpuFlags::Z, cpu.y == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.y & 0x80) != 0);
-        1
     }
 }
 
 pub struct LSR;
 impl Operation for LSR {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
         cpu.set_flag(CpuFlags::C, (cpu.fetched & 0x0001) != 0);
         cpu.temp = (cpu.fetched >> 1) as u16;
@@ -515,52 +370,40 @@ impl Operation for LSR {
         } else {
             cpu.bus.write(cpu.addr_abs, (cpu.temp & 0x00FF) as u8)
         }
-
-        0
     }
 }
 
 pub struct NOP;
 impl Operation for NOP {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        // Not all nops are equal
-        // TODO: Implement illegal opcodes
-
-        let extra = match cpu.opcode {
-            0x1C | 0x3C | 0x5C | 0x7C | 0xDC | 0xFC => 1,
-            _ => 0,
-        };
-
-        extra
+    fn execute(cpu: &mut Cpu) {
+        // Unofficial NOPs with an operand still read it
+        cpu.fetch();
     }
 }
 
 pub struct ORA;
 impl Operation for ORA {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
         cpu.a = cpu.a | cpu.fetched;
 
         cpu.set_flag(CpuFlags::Z, cpu.a == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.a & 0x80) != 0);
-
-        1
     }
 }
 
 pub struct PHA;
 impl Operation for PHA {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.bus
             .write(constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16), cpu.a);
         cpu.stkp -= 1;
-        0
     }
 }
 
 pub struct PHP;
 impl Operation for PHP {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.bus.write(
             constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16),
             cpu.status | CpuFlags::B.0 | CpuFlags::U.0,
@@ -569,13 +412,12 @@ impl Operation for PHP {
         cpu.set_flag(CpuFlags::U, false);
 
         cpu.stkp -= 1;
-        0
     }
 }
 
 pub struct PLA;
 impl Operation for PLA {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.stkp += 1;
         cpu.a = cpu
             .bus
@@ -583,30 +425,26 @@ impl Operation for PLA {
 
         cpu.set_flag(CpuFlags::Z, cpu.a == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.a & 0x80) != 0);
-
-        0
     }
 }
 
 pub struct PLP;
 impl Operation for PLP {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.stkp += 1;
         cpu.status = cpu
             .bus
             .read(constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16));
 
         cpu.set_flag(CpuFlags::U, true);
-
-        0
     }
 }
 
 pub struct ROL;
 impl Operation for ROL {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
-        cpu.temp = ((cpu.fetched << 1) as u16) | (cpu.get_flag(CpuFlags::C) as u16);
+        cpu.temp = ((cpu.fetched as u16) << 1) | (cpu.get_flag(CpuFlags::C) as u16);
 
         cpu.set_flag(CpuFlags::C, (cpu.temp & 0xFF00) != 0x0000);
         cpu.set_flag(CpuFlags::Z, (cpu.temp & 0x00FF) == 0x0000);
@@ -620,14 +458,12 @@ impl Operation for ROL {
         } else {
             cpu.bus.write(cpu.addr_abs, (cpu.temp & 0x00FF) as u8)
         }
-
-        0
     }
 }
 
 pub struct ROR;
 impl Operation for ROR {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.fetch();
         cpu.temp = ((cpu.get_flag(CpuFlags::C) << 7) as u16) | ((cpu.fetched >> 1) as u16);
 
@@ -643,161 +479,312 @@ impl Operation for ROR {
         } else {
             cpu.bus.write(cpu.addr_abs, (cpu.temp & 0x00FF) as u8)
         }
-
-        0
     }
 }
 
+// The status and return address were pulled in the cycles before
 pub struct RTI;
 impl Operation for RTI {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        cpu.stkp += 1;
-        cpu.status = cpu
-            .bus
-            .read(constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16));
-        cpu.status &= !CpuFlags::B.0;
-        cpu.status &= !CpuFlags::U.0;
-
-        cpu.stkp += 1;
-        cpu.pc = cpu
-            .bus
-            .read(constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16)) as u16;
-        cpu.stkp += 1;
-        cpu.pc |= (cpu
-            .bus
-            .read(constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16)) as u16)
-            << 8;
-        0
+    fn execute(cpu: &mut Cpu) {
+        cpu.pc = cpu.addr_abs;
     }
 }
 
+// The return address was pulled in the cycles before
 pub struct RTS;
 impl Operation for RTS {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
-        cpu.stkp += 1;
-        cpu.pc = cpu
-            .bus
-            .read(constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16)) as u16;
-        cpu.stkp += 1;
-        cpu.pc |= (cpu
-            .bus
-            .read(constants::cpu::STACK_BASE_ADDR + (cpu.stkp as u16)) as u16)
-            << 8;
-
-        cpu.pc += 1;
-        0
+    fn execute(cpu: &mut Cpu) {
+        cpu.pc = cpu.addr_abs.wrapping_add(1);
     }
 }
 
 pub struct SEC;
 impl Operation for SEC {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.set_flag(CpuFlags::C, true);
-        0
     }
 }
 
 pub struct SED;
 impl Operation for SED {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.set_flag(CpuFlags::D, true);
-        0
     }
 }
 
 pub struct SEI;
 impl Operation for SEI {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.set_flag(CpuFlags::I, true);
-        0
     }
 }
 
 pub struct STA;
 impl Operation for STA {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.bus.write(cpu.addr_abs, cpu.a);
-        0
     }
 }
 
 pub struct STX;
 impl Operation for STX {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.bus.write(cpu.addr_abs, cpu.x);
-        0
     }
 }
 
 pub struct STY;
 impl Operation for STY {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.bus.write(cpu.addr_abs, cpu.y);
-        0
     }
 }
 
 pub struct TAX;
 impl Operation for TAX {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.x = cpu.a;
         cpu.set_flag(CpuFlags::Z, cpu.x == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.x & 0x80) != 0);
-        0
     }
 }
 
 pub struct TAY;
 impl Operation for TAY {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.y = cpu.a;
         cpu.set_flag(CpuFlags::Z, cpu.y == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.y & 0x80) != 0);
-        0
     }
 }
 
 pub struct TSX;
 impl Operation for TSX {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.x = cpu.stkp;
         cpu.set_flag(CpuFlags::Z, cpu.x == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.x & 0x80) != 0);
-        0
     }
 }
 
 pub struct TXA;
 impl Operation for TXA {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.a = cpu.x;
         cpu.set_flag(CpuFlags::Z, cpu.a == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.a & 0x80) != 0);
-        0
     }
 }
 
 pub struct TXS;
 impl Operation for TXS {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.stkp = cpu.x;
-        0
     }
 }
 
 pub struct TYA;
 impl Operation for TYA {
-    fn execute(&self, cpu: &mut Cpu) -> u8 {
+    fn execute(cpu: &mut Cpu) {
         cpu.a = cpu.y;
         cpu.set_flag(CpuFlags::Z, cpu.a == 0x00);
         cpu.set_flag(CpuFlags::N, (cpu.a & 0x80) != 0);
-        0
     }
 }
 
-pub struct XXX;
-impl Operation for XXX {
-    fn execute(&self, _cpu: &mut Cpu) -> u8 {
-        0
+// Unofficial Operators
+pub struct ALR;
+impl Operation for ALR {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        let value = cpu.a & cpu.fetched;
+        cpu.set_flag(CpuFlags::C, (value & 0x01) != 0);
+        cpu.a = value >> 1;
+        set_zero_negative(cpu, cpu.a);
+    }
+}
+
+pub struct ANC;
+impl Operation for ANC {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        cpu.a &= cpu.fetched;
+        set_zero_negative(cpu, cpu.a);
+        cpu.set_flag(CpuFlags::C, (cpu.a & 0x80) != 0);
+    }
+}
+
+pub struct ARR;
+impl Operation for ARR {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        cpu.a = ((cpu.a & cpu.fetched) >> 1) | (cpu.get_flag(CpuFlags::C) << 7);
+        set_zero_negative(cpu, cpu.a);
+        cpu.set_flag(CpuFlags::C, (cpu.a & 0x40) != 0);
+        cpu.set_flag(CpuFlags::V, ((cpu.a >> 6) ^ (cpu.a >> 5)) & 0x01 != 0);
+    }
+}
+
+pub struct AXS;
+impl Operation for AXS {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        let value = cpu.a & cpu.x;
+        compare(cpu, value, cpu.fetched);
+        cpu.x = value.wrapping_sub(cpu.fetched);
+    }
+}
+
+pub struct DCP;
+impl Operation for DCP {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        let value = cpu.fetched.wrapping_sub(1);
+        cpu.bus.write(cpu.addr_abs, value);
+        compare(cpu, cpu.a, value);
+    }
+}
+
+pub struct ISC;
+impl Operation for ISC {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        let value = cpu.fetched.wrapping_add(1);
+        cpu.bus.write(cpu.addr_abs, value);
+        add_with_carry(cpu, value ^ 0xFF);
+    }
+}
+
+// Locks up the CPU until the next reset
+pub struct JAM;
+impl Operation for JAM {
+    fn execute(cpu: &mut Cpu) {
+        cpu.pc -= 1;
+    }
+}
+
+pub struct LAS;
+impl Operation for LAS {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        let value = cpu.fetched & cpu.stkp;
+        cpu.a = value;
+        cpu.x = value;
+        cpu.stkp = value;
+        set_zero_negative(cpu, value);
+    }
+}
+
+pub struct LAX;
+impl Operation for LAX {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        cpu.a = cpu.fetched;
+        cpu.x = cpu.fetched;
+        set_zero_negative(cpu, cpu.a);
+    }
+}
+
+// Unstable, the ORed constant depends on the chip, $EE is the most common
+pub struct LXA;
+impl Operation for LXA {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        cpu.a = (cpu.a | 0xEE) & cpu.fetched;
+        cpu.x = cpu.a;
+        set_zero_negative(cpu, cpu.a);
+    }
+}
+
+pub struct RLA;
+impl Operation for RLA {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        let value = (cpu.fetched << 1) | cpu.get_flag(CpuFlags::C);
+        cpu.set_flag(CpuFlags::C, (cpu.fetched & 0x80) != 0);
+        cpu.bus.write(cpu.addr_abs, value);
+        cpu.a &= value;
+        set_zero_negative(cpu, cpu.a);
+    }
+}
+
+pub struct RRA;
+impl Operation for RRA {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        let value = (cpu.fetched >> 1) | (cpu.get_flag(CpuFlags::C) << 7);
+        cpu.set_flag(CpuFlags::C, (cpu.fetched & 0x01) != 0);
+        cpu.bus.write(cpu.addr_abs, value);
+        add_with_carry(cpu, value);
+    }
+}
+
+pub struct SAX;
+impl Operation for SAX {
+    fn execute(cpu: &mut Cpu) {
+        cpu.bus.write(cpu.addr_abs, cpu.a & cpu.x);
+    }
+}
+
+pub struct SHA;
+impl Operation for SHA {
+    fn execute(cpu: &mut Cpu) {
+        store_high_and(cpu, cpu.a & cpu.x, cpu.y);
+    }
+}
+
+pub struct SHX;
+impl Operation for SHX {
+    fn execute(cpu: &mut Cpu) {
+        store_high_and(cpu, cpu.x, cpu.y);
+    }
+}
+
+pub struct SHY;
+impl Operation for SHY {
+    fn execute(cpu: &mut Cpu) {
+        store_high_and(cpu, cpu.y, cpu.x);
+    }
+}
+
+pub struct SLO;
+impl Operation for SLO {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        let value = cpu.fetched << 1;
+        cpu.set_flag(CpuFlags::C, (cpu.fetched & 0x80) != 0);
+        cpu.bus.write(cpu.addr_abs, value);
+        cpu.a |= value;
+        set_zero_negative(cpu, cpu.a);
+    }
+}
+
+pub struct SRE;
+impl Operation for SRE {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        let value = cpu.fetched >> 1;
+        cpu.set_flag(CpuFlags::C, (cpu.fetched & 0x01) != 0);
+        cpu.bus.write(cpu.addr_abs, value);
+        cpu.a ^= value;
+        set_zero_negative(cpu, cpu.a);
+    }
+}
+
+pub struct TAS;
+impl Operation for TAS {
+    fn execute(cpu: &mut Cpu) {
+        cpu.stkp = cpu.a & cpu.x;
+        store_high_and(cpu, cpu.stkp, cpu.y);
+    }
+}
+
+// Unstable, the ORed constant depends on the chip, $EE is the most common
+pub struct XAA;
+impl Operation for XAA {
+    fn execute(cpu: &mut Cpu) {
+        cpu.fetch();
+        cpu.a = (cpu.a | 0xEE) & cpu.x & cpu.fetched;
+        set_zero_negative(cpu, cpu.a);
     }
 }
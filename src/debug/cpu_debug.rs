@@ -119,6 +119,46 @@ impl CpuDebug {
         str
     }
 
+    pub fn get_debug_cartridge(&self, nes: &mut Nes) -> String {
+        let cartridge = nes.cpu.bus.get_cartridge().borrow();
+        let header = cartridge.header();
+        let mut str = String::from("");
+
+        str.push_str(&format!("Format: {:?}\n", header.format));
+        str.push_str(&format!(
+            "Mapper: {} [Submapper {}]\n",
+            header.mapper_id, header.submapper_id
+        ));
+        str.push_str(&format!(
+            "PRG-ROM: {} KiB  CHR-ROM: {} KiB\n",
+            header.prg_rom_size / 1024,
+            header.chr_rom_size / 1024
+        ));
+        str.push_str(&format!(
+            "PRG-RAM: {} B  PRG-NVRAM: {} B\n",
+            header.prg_ram_size, header.prg_nvram_size
+        ));
+        str.push_str(&format!(
+            "CHR-RAM: {} B  CHR-NVRAM: {} B\n",
+            header.chr_ram_size, header.chr_nvram_size
+        ));
+        str.push_str(&format!(
+            "Mirror: {:?}  Battery: {}  Trainer: {}\n",
+            header.mirror, header.battery, header.trainer
+        ));
+        str.push_str(&format!(
+            "Timing: {:?}  Console: {:?}\n",
+            header.timing, header.console_type
+        ));
+        str.push_str(&format!(
+            "Misc ROMs: {}  Expansion: 0x{}",
+            header.misc_roms,
+            Disassembler::hex(header.expansion_device as u32, 2)
+        ));
+
+        str
+    }
+
     pub fn get_debug_ram(&self, nes: &mut Nes, start: u16, rows: u32, cols: u32) -> String {
         // let io = IODevice::new(&mut nes: &mut Nes.ram, &mut self.ppu_ram, &mut self.cartridge);
         let mut str = String::from("");
@@ -129,7 +169,7 @@ impl CpuDebug {
                 str.push_str(
                     &[
                         " ",
-                        &Disassembler::hex(nes.cpu.bus.read(start + offset) as u32, 2),
+                        &Disassembler::hex(nes.cpu.bus.peek(start + offset) as u32, 2),
                     ]
                     .join(""),
                 );
@@ -157,66 +197,63 @@ impl Disassembler {
         let mut instructions: Vec<String> = vec![];
         let mut instr_index: u16 = 0;
         let mut addr: u32 = start_addr as u32;
-        let mut value: u8;
-        let mut lo: u8;
-        let mut hi: u8;
         let mut line_addr: u16;
 
         while addr <= (end_addr as u32) {
             line_addr = addr as u16;
             let mut instruction_str = ["0x", &Disassembler::hex(addr, 4), ": "].join("");
-            let opcode: u8 = bus.read(addr as u16);
+            let opcode: u8 = bus.peek(addr as u16);
             addr += 1;
             let instr = Instruction::from_opcode(opcode);
-            instruction_str.push_str(&instr.get_name());
+            instruction_str.push_str(instr.get_name());
             instruction_str.push_str(" ");
 
+            // Operand bytes, the table knows how many follow the opcode
+            let lo: u8 = if instr.get_bytes() > 1 {
+                bus.peek(addr as u16)
+            } else {
+                0x00
+            };
+            let hi: u8 = if instr.get_bytes() > 2 {
+                bus.peek((addr + 1) as u16)
+            } else {
+                0x00
+            };
+            addr += (instr.get_bytes() - 1) as u32;
+            let word = (((hi as u16) << 8) | lo as u16) as u32;
+
             match instr.get_addrmode() {
                 AddrMode::IMP => {
                     instruction_str.push_str(" {IMP}");
                 }
                 AddrMode::IMM => {
-                    value = bus.read(addr as u16);
-                    addr += 1;
                     instruction_str
-                        .push_str(&["#0x", &Disassembler::hex(value as u32, 2), " {IMM}"].join(""));
+                        .push_str(&["#0x", &Disassembler::hex(lo as u32, 2), " {IMM}"].join(""));
                 }
                 AddrMode::ZP0 => {
-                    lo = bus.read(addr as u16);
-                    // hi = 0x00;
-                    addr += 1;
                     instruction_str
                         .push_str(&["0x", &Disassembler::hex(lo as u32, 2), " {ZP0}"].join(""));
                 }
-
                 AddrMode::ZPX => {
-                    lo = bus.read(addr as u16);
-                    // hi = 0x00;
-                    addr += 1;
                     instruction_str
                         .push_str(&["0x", &Disassembler::hex(lo as u32, 2), ", X {ZPX}"].join(""));
                 }
                 AddrMode::ZPY => {
-                    lo = bus.read(addr as u16);
-                    // hi = 0x00;
-                    addr += 1;
                     instruction_str
                         .push_str(&["0x", &Disassembler::hex(lo as u32, 2), ", Y {ZPY}"].join(""));
                 }
                 AddrMode::REL => {
-                    value = bus.read(addr as u16);
-                    addr += 1;
                     let dest = {
-                        if (value & 0x80) > 0 {
-                            addr - ((!value + 1) as u32)
+                        if (lo & 0x80) > 0 {
+                            addr - ((!lo + 1) as u32)
                         } else {
-                            addr + (value as u32)
+                            addr + (lo as u32)
                         }
                     };
                     instruction_str.push_str(
                         &[
                             "0x",
-                            &Disassembler::hex(value as u32, 2),
+                            &Disassembler::hex(lo as u32, 2),
                             " [0x",
                             &Disassembler::hex(dest, 4),
                             "] {REL}",
@@ -225,78 +262,34 @@ impl Disassembler {
                     );
                 }
                 AddrMode::ABS => {
-                    lo = bus.read(addr as u16);
-                    addr += 1;
-                    hi = bus.read(addr as u16);
-                    addr += 1;
-                    instruction_str.push_str(
-                        &[
-                            "0x",
-                            &Disassembler::hex((((hi as u16) << 8) | lo as u16) as u32, 4),
-                            " {ABS}",
-                        ]
-                        .join(""),
-                    );
+                    instruction_str
+                        .push_str(&["0x", &Disassembler::hex(word, 4), " {ABS}"].join(""));
                 }
                 AddrMode::ABX => {
-                    lo = bus.read(addr as u16);
-                    addr += 1;
-                    hi = bus.read(addr as u16);
-                    addr += 1;
-                    instruction_str.push_str(
-                        &[
-                            "0x",
-                            &Disassembler::hex((((hi as u16) << 8) | lo as u16) as u32, 4),
-                            ", X {ABX}",
-                        ]
-                        .join(""),
-                    );
+                    instruction_str
+                        .push_str(&["0x", &Disassembler::hex(word, 4), ", X {ABX}"].join(""));
                 }
                 AddrMode::ABY => {
-                    lo = bus.read(addr as u16);
-                    addr += 1;
-                    hi = bus.read(addr as u16);
-                    addr += 1;
-                    instruction_str.push_str(
-                        &[
-                            "0x",
-                            &Disassembler::hex((((hi as u16) << 8) | lo as u16) as u32, 4),
-                            ", Y {ABY}",
-                        ]
-                        .join(""),
-                    );
+                    instruction_str
+                        .push_str(&["0x", &Disassembler::hex(word, 4), ", Y {ABY}"].join(""));
                 }
                 AddrMode::IND => {
-                    lo = bus.read(addr as u16);
-                    addr += 1;
-                    hi = bus.read(addr as u16);
-                    addr += 1;
-                    instruction_str.push_str(
-                        &[
-                            "0x",
-                            &Disassembler::hex((((hi as u16) << 8) | lo as u16) as u32, 4),
-                            " {IND}",
-                        ]
-                        .join(""),
-                    );
+                    instruction_str
+                        .push_str(&["0x", &Disassembler::hex(word, 4), " {IND}"].join(""));
                 }
                 AddrMode::IZX => {
-                    lo = bus.read(addr as u16);
-                    // hi = 0x00;
-                    addr += 1;
                     instruction_str.push_str(
                         &["(0x", &Disassembler::hex(lo as u32, 2), ", X) {IZX}"].join(""),
                     );
                 }
                 AddrMode::IZY => {
-                    lo = bus.read(addr as u16);
-                    // hi = 0x00;
-                    addr += 1;
                     instruction_str.push_str(
                         &["(0x", &Disassembler::hex(lo as u32, 2), ", Y) {IZY}"].join(""),
                     );
                 }
             }
+            // Base cycle count
+            instruction_str.push_str(&[" ", &instr.get_cycles().to_string(), "c"].join(""));
             instructions.push(instruction_str);
             lines_map.insert(line_addr, instr_index);
             instr_index += 1;
@@ -1,14 +1,22 @@
-use std::env;
-
 use ggez::{
-    event::EventHandler,
+    event::{Axis, Button as PadButton, EventHandler},
     glam::Vec2,
     graphics::{Canvas, Color, Text},
-    input::keyboard::KeyCode,
+    input::gamepad::GamepadId,
     Context, GameError, GameResult,
 };
 
-use crate::nes::Nes;
+use crate::{
+    audio::audio_stream::AudioStream,
+    constants,
+    input::{
+        gamepad_input::GamepadInput,
+        key_bindings::{Action, KeyBindings},
+    },
+    nes::Nes,
+    options::Options,
+    view::game_view::GameView,
+};
 
 use super::cpu_debug::CpuDebug;
 
@@ -16,6 +24,12 @@ pub struct CpuView {
     nes: Nes,
     debug: CpuDebug,
     exec_state: ExecState,
+    game: GameView,
+    bindings: KeyBindings,
+    gamepads: GamepadInput,
+    audio: Option<AudioStream>,
+    show_debug: bool,
+    error: Option<String>,
 }
 
 #[derive(PartialEq)]
@@ -23,21 +37,49 @@ enum ExecState {
     STEPPING,
     RUN,
     UPDATE,
+    // Runs in real time with sound
+    PLAY,
 }
 
 impl CpuView {
-    pub fn new() -> GameResult<CpuView> {
+    pub fn new(ctx: &mut Context, options: &Options) -> GameResult<CpuView> {
         let mut nes = Nes::new();
-        let args: Vec<String> = env::args().collect();
-        let rom_file = &args[1];
-        nes.insert_cartridge(rom_file);
+        let rom_file = &options.rom_file;
+        let error = match nes.insert_cartridge(rom_file) {
+            Ok(()) => None,
+            Err(err) => {
+                let message = format!("Could not load '{}':\n{}", rom_file, err);
+                println!("Error: {}", message);
+                Some(message)
+            }
+        };
+
+        if let Some(wav_file) = &options.wav_file {
+            if let Err(err) = nes.start_wav_dump(wav_file, options.wav_channels) {
+                println!("Error: Could not create WAV file '{}': {}", wav_file, err);
+            }
+        }
 
         let debug = CpuDebug::new(&mut nes);
 
+        let audio = match AudioStream::new(ctx.audio.device(), constants::audio::SAMPLE_RATE) {
+            Ok(stream) => Some(stream),
+            Err(err) => {
+                println!("Warning: Could not open audio output: {}", err);
+                None
+            }
+        };
+
         let s: CpuView = CpuView {
             nes,
             debug,
             exec_state: ExecState::STEPPING,
+            game: GameView::new(ctx),
+            bindings: KeyBindings::new(),
+            gamepads: GamepadInput::new(),
+            audio,
+            show_debug: true,
+            error,
         };
         Ok(s)
     }
@@ -45,24 +87,44 @@ impl CpuView {
 
 impl EventHandler<GameError> for CpuView {
     fn update(&mut self, ctx: &mut Context) -> Result<(), GameError> {
+        if self.error.is_some() {
+            return Ok(());
+        }
+
         let keyboard = &ctx.keyboard;
-        if keyboard.is_key_just_pressed(KeyCode::M) {
+        if self.bindings.just_pressed(keyboard, Action::ToggleUpdate) {
             self.exec_state = if self.exec_state != ExecState::UPDATE {
                 ExecState::UPDATE
             } else {
                 ExecState::STEPPING
             };
         }
-        if keyboard.is_key_just_pressed(KeyCode::B) {
+        if self.bindings.just_pressed(keyboard, Action::ToggleRun) {
             self.exec_state = if self.exec_state != ExecState::RUN {
                 ExecState::RUN
             } else {
                 ExecState::STEPPING
             };
         }
-        if keyboard.is_key_just_pressed(KeyCode::N) {
+        if self.bindings.just_pressed(keyboard, Action::TogglePlay) {
+            self.exec_state = if self.exec_state != ExecState::PLAY {
+                ExecState::PLAY
+            } else {
+                ExecState::STEPPING
+            };
+        }
+        if self.bindings.just_pressed(keyboard, Action::Step) {
             self.exec_state = ExecState::STEPPING;
         }
+        if self.bindings.just_pressed(keyboard, Action::ToggleDebug) {
+            self.show_debug = !self.show_debug;
+        }
+
+        self.gamepads.update_connections(&ctx.gamepad);
+        for (port, controller) in self.nes.cpu.bus.controllers.iter_mut().enumerate() {
+            controller
+                .set_buttons(self.bindings.buttons(keyboard, port) | self.gamepads.buttons(port));
+        }
 
         match self.exec_state {
             ExecState::RUN => {
@@ -71,28 +133,110 @@ impl EventHandler<GameError> for CpuView {
             ExecState::UPDATE => {
                 self.nes.update(ctx.time.delta());
             }
+            ExecState::PLAY => {
+                self.nes.play(ctx.time.delta());
+            }
             ExecState::STEPPING => {
-                if keyboard.is_key_just_pressed(KeyCode::N) {
+                if self.bindings.just_pressed(keyboard, Action::Step) {
                     self.nes.step();
                 }
             }
         };
 
+        let samples = self.nes.audio.take_samples();
+        if let Some(audio) = &mut self.audio {
+            audio.push(&samples);
+        }
+
+        self.game.update(ctx, &mut self.nes.cpu.bus.ppu);
+        self.nes.update_save(ctx.time.delta());
+
+        Ok(())
+    }
+
+    fn gamepad_button_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        btn: PadButton,
+        id: GamepadId,
+    ) -> Result<(), GameError> {
+        self.gamepads.button_down(id, btn);
+        Ok(())
+    }
+
+    fn gamepad_button_up_event(
+        &mut self,
+        _ctx: &mut Context,
+        btn: PadButton,
+        id: GamepadId,
+    ) -> Result<(), GameError> {
+        self.gamepads.button_up(id, btn);
+        Ok(())
+    }
+
+    fn gamepad_axis_event(
+        &mut self,
+        _ctx: &mut Context,
+        axis: Axis,
+        value: f32,
+        id: GamepadId,
+    ) -> Result<(), GameError> {
+        self.gamepads.axis(id, axis, value);
         Ok(())
     }
 
+    fn quit_event(&mut self, _ctx: &mut Context) -> Result<bool, GameError> {
+        self.nes.flush_save();
+        self.nes.stop_wav_dump();
+        Ok(false)
+    }
+
     fn draw(&mut self, _ctx: &mut Context) -> Result<(), GameError> {
         let mut canvas = Canvas::from_frame(_ctx, Color::BLACK);
 
+        if let Some(error) = &self.error {
+            canvas.draw(&Text::new(error.as_str()), Vec2::new(10.0, 10.0));
+            canvas.finish(_ctx)?;
+            return Ok(());
+        }
+
+        if !self.show_debug {
+            // Largest integer scale that still fits into the window
+            let scale = (constants::emulator::WINDOW_WIDTH / GameView::width(1.0))
+                .min(constants::emulator::WINDOW_HEIGHT / GameView::height(1.0))
+                .floor();
+            let dest = Vec2::new(
+                (constants::emulator::WINDOW_WIDTH - GameView::width(scale)) / 2.0,
+                (constants::emulator::WINDOW_HEIGHT - GameView::height(scale)) / 2.0,
+            );
+            self.game.draw(&mut canvas, dest, scale);
+            canvas.finish(_ctx)?;
+            return Ok(());
+        }
+
+        self.game.draw(&mut canvas, Vec2::new(10.0, 10.0), 2.0);
+
         let code_txt = Text::new(self.debug.get_debug_code(&mut self.nes));
         let regs_txt = Text::new(self.debug.get_debug_registers(&mut self.nes));
         let ram1_txt = Text::new(self.debug.get_debug_ram(&mut self.nes, 0x0000, 16, 16));
         let cart_txt = Text::new(self.debug.get_debug_ram(&mut self.nes, 0x8000, 16, 16));
+        let header_txt = Text::new(self.debug.get_debug_cartridge(&mut self.nes));
 
-        canvas.draw(&code_txt, Vec2::new(500.0, 140.0));
-        canvas.draw(&regs_txt, Vec2::new(500.0, 10.0));
-        canvas.draw(&ram1_txt, Vec2::new(10.0, 10.0));
-        canvas.draw(&cart_txt, Vec2::new(10.0, 285.0));
+        canvas.draw(&code_txt, Vec2::new(1032.0, 140.0));
+        canvas.draw(&regs_txt, Vec2::new(1032.0, 10.0));
+        canvas.draw(&ram1_txt, Vec2::new(532.0, 10.0));
+        canvas.draw(&cart_txt, Vec2::new(532.0, 285.0));
+        canvas.draw(&header_txt, Vec2::new(10.0, 500.0));
+
+        let audio_txt = match &self.audio {
+            Some(audio) => Text::new(format!(
+                "Audio: {:.0} ms latency, {} underruns",
+                audio.latency().as_secs_f64() * 1000.0,
+                audio.underruns()
+            )),
+            None => Text::new("Audio: unavailable"),
+        };
+        canvas.draw(&audio_txt, Vec2::new(532.0, 560.0));
         canvas.finish(_ctx)?;
         Ok(())
     }
@@ -1,14 +1,42 @@
-use ggez::{event::run, ContextBuilder};
+use ggez::{conf::WindowMode, event::run, ContextBuilder};
 
-use crate::{constants, debug::cpu_view::CpuView};
+use crate::{constants, debug::cpu_view::CpuView, nes::Nes, options::Options};
 
-pub fn start() -> ! {
+pub fn start(options: &Options) -> ! {
     let cb = ContextBuilder::new(
         constants::emulator::GAME_ID,
         constants::emulator::AUTHOR_NAME,
-    );
-    let (ctx, event_loop) = cb.build().unwrap();
-    let view = CpuView::new().unwrap();
+    )
+    .window_mode(WindowMode::default().dimensions(
+        constants::emulator::WINDOW_WIDTH,
+        constants::emulator::WINDOW_HEIGHT,
+    ));
+    let (mut ctx, event_loop) = cb.build().unwrap();
+    let view = CpuView::new(&mut ctx, options).unwrap();
 
     run(ctx, event_loop, view);
 }
+
+// Emulates as fast as possible without a window or audio device, e.g. for WAV dumps on CI
+pub fn run_headless(options: &Options) {
+    let mut nes = Nes::new();
+    if let Err(err) = nes.insert_cartridge(&options.rom_file) {
+        println!("Error: Could not load '{}':\n{}", options.rom_file, err);
+        return;
+    }
+    if let Some(wav_file) = &options.wav_file {
+        if let Err(err) = nes.start_wav_dump(wav_file, options.wav_channels) {
+            println!("Error: Could not create WAV file '{}': {}", wav_file, err);
+            return;
+        }
+    }
+
+    for _ in 0..options.headless_frames.unwrap_or(0) {
+        nes.frame();
+        // Nothing plays the samples, drop them
+        nes.audio.take_samples();
+    }
+
+    nes.stop_wav_dump();
+    nes.flush_save();
+}
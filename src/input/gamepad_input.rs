@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use ggez::input::gamepad::{
+    gilrs::{Axis, Button as PadButton},
+    GamepadContext, GamepadId,
+};
+
+use crate::controller::controller::Button;
+
+pub const PORT_COUNT: usize = 2;
+
+// Tracks the gamepads assigned to the controller ports and turns their events
+// into per-frame button state
+pub struct GamepadInput {
+    ports: [Option<GamepadId>; PORT_COUNT],
+    bindings: HashMap<PadButton, Button>,
+    // How far a stick has to be pushed before it counts as a D-pad direction
+    pub axis_threshold: f32,
+
+    held: [Button; PORT_COUNT],
+    stick: [(f32, f32); PORT_COUNT],
+}
+
+impl GamepadInput {
+    pub fn new() -> GamepadInput {
+        let mut input = GamepadInput {
+            ports: [None; PORT_COUNT],
+            bindings: HashMap::new(),
+            axis_threshold: 0.5,
+            held: [Button::empty(); PORT_COUNT],
+            stick: [(0.0, 0.0); PORT_COUNT],
+        };
+
+        // Face buttons follow the position of A and B on the NES pad
+        input.bind(PadButton::East, Button::A);
+        input.bind(PadButton::South, Button::B);
+        input.bind(PadButton::Select, Button::SELECT);
+        input.bind(PadButton::Start, Button::START);
+        input.bind(PadButton::DPadUp, Button::UP);
+        input.bind(PadButton::DPadDown, Button::DOWN);
+        input.bind(PadButton::DPadLeft, Button::LEFT);
+        input.bind(PadButton::DPadRight, Button::RIGHT);
+
+        input
+    }
+
+    pub fn bind(&mut self, pad_button: PadButton, button: Button) {
+        self.bindings.insert(pad_button, button);
+    }
+
+    // Assigns a gamepad to a port, taking it away from any other port
+    pub fn assign(&mut self, port: usize, id: Option<GamepadId>) {
+        for other in 0..PORT_COUNT {
+            if id.is_some() && self.ports[other] == id {
+                // Whatever it held on the old port is let go
+                self.ports[other] = None;
+                self.release(other);
+            }
+        }
+        self.ports[port] = id;
+        self.release(port);
+    }
+
+    // Frees the ports of unplugged gamepads and gives newly plugged ones the first free port
+    pub fn update_connections(&mut self, gamepads: &GamepadContext) {
+        let connected: Vec<GamepadId> = gamepads.gamepads().map(|(id, _)| id).collect();
+
+        for port in 0..PORT_COUNT {
+            if let Some(id) = self.ports[port] {
+                if !connected.contains(&id) {
+                    self.assign(port, None);
+                }
+            }
+        }
+
+        for id in connected {
+            if self.ports.contains(&Some(id)) {
+                continue;
+            }
+            match self.ports.iter().position(|port| port.is_none()) {
+                Some(port) => self.assign(port, Some(id)),
+                None => break,
+            }
+        }
+    }
+
+    pub fn button_down(&mut self, id: GamepadId, pad_button: PadButton) {
+        if let (Some(port), Some(button)) = (self.port_of(id), self.bindings.get(&pad_button)) {
+            self.held[port] |= *button;
+        }
+    }
+
+    pub fn button_up(&mut self, id: GamepadId, pad_button: PadButton) {
+        if let (Some(port), Some(button)) = (self.port_of(id), self.bindings.get(&pad_button)) {
+            self.held[port] &= !*button;
+        }
+    }
+
+    pub fn axis(&mut self, id: GamepadId, axis: Axis, value: f32) {
+        let Some(port) = self.port_of(id) else {
+            return;
+        };
+        match axis {
+            Axis::LeftStickX | Axis::DPadX => self.stick[port].0 = value,
+            Axis::LeftStickY | Axis::DPadY => self.stick[port].1 = value,
+            _ => {}
+        }
+    }
+
+    // Buttons held on the gamepad of the given port, with the stick folded into the D-pad
+    pub fn buttons(&self, port: usize) -> Button {
+        let (x, y) = self.stick[port];
+        let mut buttons = self.held[port];
+        buttons.set(
+            Button::LEFT,
+            buttons.contains(Button::LEFT) || x < -self.axis_threshold,
+        );
+        buttons.set(
+            Button::RIGHT,
+            buttons.contains(Button::RIGHT) || x > self.axis_threshold,
+        );
+        // Stick Y points up
+        buttons.set(
+            Button::UP,
+            buttons.contains(Button::UP) || y > self.axis_threshold,
+        );
+        buttons.set(
+            Button::DOWN,
+            buttons.contains(Button::DOWN) || y < -self.axis_threshold,
+        );
+        buttons
+    }
+
+    fn port_of(&self, id: GamepadId) -> Option<usize> {
+        self.ports.iter().position(|port| *port == Some(id))
+    }
+
+    fn release(&mut self, port: usize) {
+        self.held[port] = Button::empty();
+        self.stick[port] = (0.0, 0.0);
+    }
+}
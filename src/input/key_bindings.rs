@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use ggez::input::keyboard::{KeyCode, KeyboardContext};
+
+use crate::controller::controller::Button;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    // Button of the controller in the given port
+    Controller(usize, Button),
+    ToggleRun,
+    ToggleUpdate,
+    TogglePlay,
+    Step,
+    ToggleDebug,
+}
+
+// Maps keyboard keys to controller buttons and emulator actions,
+// several keys may trigger the same action
+pub struct KeyBindings {
+    bindings: HashMap<KeyCode, Action>,
+}
+
+impl KeyBindings {
+    pub fn new() -> KeyBindings {
+        let mut bindings = KeyBindings {
+            bindings: HashMap::new(),
+        };
+
+        bindings.bind(KeyCode::X, Action::Controller(0, Button::A));
+        bindings.bind(KeyCode::Z, Action::Controller(0, Button::B));
+        bindings.bind(KeyCode::RShift, Action::Controller(0, Button::SELECT));
+        bindings.bind(KeyCode::Return, Action::Controller(0, Button::START));
+        bindings.bind(KeyCode::Up, Action::Controller(0, Button::UP));
+        bindings.bind(KeyCode::Down, Action::Controller(0, Button::DOWN));
+        bindings.bind(KeyCode::Left, Action::Controller(0, Button::LEFT));
+        bindings.bind(KeyCode::Right, Action::Controller(0, Button::RIGHT));
+
+        bindings.bind(KeyCode::O, Action::Controller(1, Button::A));
+        bindings.bind(KeyCode::U, Action::Controller(1, Button::B));
+        bindings.bind(KeyCode::Y, Action::Controller(1, Button::SELECT));
+        bindings.bind(KeyCode::P, Action::Controller(1, Button::START));
+        bindings.bind(KeyCode::I, Action::Controller(1, Button::UP));
+        bindings.bind(KeyCode::K, Action::Controller(1, Button::DOWN));
+        bindings.bind(KeyCode::J, Action::Controller(1, Button::LEFT));
+        bindings.bind(KeyCode::L, Action::Controller(1, Button::RIGHT));
+
+        bindings.bind(KeyCode::B, Action::ToggleRun);
+        bindings.bind(KeyCode::M, Action::ToggleUpdate);
+        bindings.bind(KeyCode::Space, Action::TogglePlay);
+        bindings.bind(KeyCode::N, Action::Step);
+        bindings.bind(KeyCode::G, Action::ToggleDebug);
+
+        bindings
+    }
+
+    // Replaces whatever the key was bound to before
+    pub fn bind(&mut self, key: KeyCode, action: Action) {
+        self.bindings.insert(key, action);
+    }
+
+    // Buttons of the given controller port that are currently held down
+    pub fn buttons(&self, keyboard: &KeyboardContext, port: usize) -> Button {
+        self.bindings
+            .iter()
+            .filter(|(key, _)| keyboard.is_key_pressed(**key))
+            .fold(Button::empty(), |buttons, (_, action)| match action {
+                Action::Controller(p, button) if *p == port => buttons | *button,
+                _ => buttons,
+            })
+    }
+
+    pub fn just_pressed(&self, keyboard: &KeyboardContext, action: Action) -> bool {
+        self.bindings
+            .iter()
+            .any(|(key, bound)| *bound == action && keyboard.is_key_just_pressed(*key))
+    }
+}
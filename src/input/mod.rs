@@ -0,0 +1,2 @@
+pub mod gamepad_input;
+pub mod key_bindings;
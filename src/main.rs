@@ -1,19 +1,32 @@
 use std::env;
 
+use options::Options;
+
+mod apu;
+mod audio;
 mod bus;
 mod cartridge;
 mod constants;
+mod controller;
 mod cpu;
 mod debug;
 mod emulator;
+mod input;
 mod nes;
+mod options;
 mod ppu;
 mod ram;
+mod view;
 
 #[allow(arithmetic_overflow)]
 fn main() {
-    if env::args().len() != 2 {
-        panic!("ERROR: Provide Path to ROM as Argument!")
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => panic!("ERROR: {}", err),
+    };
+    if options.headless_frames.is_some() {
+        emulator::run_headless(&options);
+    } else {
+        emulator::start(&options);
     }
-    emulator::start();
 }
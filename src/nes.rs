@@ -1,13 +1,27 @@
-use std::{cell::RefCell, rc::Rc, time::Duration};
+use std::{cell::RefCell, io, rc::Rc, time::Duration};
 
-use crate::{cartridge::cartridge::Cartridge, cpu::cpu::Cpu};
+use crate::{
+    audio::{audio::Audio, wav_dump::WavDump},
+    cartridge::{cartridge::Cartridge, rom_error::RomError},
+    constants,
+    cpu::cpu::Cpu,
+};
 
 pub const FRAME_LENGTH: Duration = Duration::from_millis(100);
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+// 60.0988 Hz NTSC refresh rate
+pub const NTSC_FRAME_LENGTH: Duration = Duration::from_nanos(16_639_267);
+// Frames to catch up at most per update, so a stall doesn't snowball
+const MAX_CATCH_UP_FRAMES: u32 = 3;
 
 pub struct Nes {
     pub cpu: Cpu,
+    pub audio: Audio,
+    wav_dump: Option<WavDump>,
     clock_counter: u64,
     frame_delta_time: f64,
+    save_delta_time: f64,
+    play_delta_time: f64,
 }
 
 impl Nes {
@@ -15,15 +29,25 @@ impl Nes {
         let cpu = Cpu::new();
         Nes {
             cpu,
+            audio: Audio::new(constants::audio::SAMPLE_RATE),
+            wav_dump: None,
             clock_counter: 0,
             frame_delta_time: 0.0,
+            save_delta_time: 0.0,
+            play_delta_time: 0.0,
         }
     }
 
-    pub fn insert_cartridge(&mut self, path: &str) {
-        let cartridge: Rc<RefCell<Cartridge>> = Rc::new(RefCell::new(Cartridge::from_file(path)));
-        self.cpu.bus.change_cartridge(cartridge);
+    pub fn insert_cartridge(&mut self, path: &str) -> Result<(), RomError> {
+        let mut cartridge = Cartridge::from_file(path)?;
+        cartridge.load_save()?;
+
+        self.flush_save();
+        self.cpu
+            .bus
+            .change_cartridge(Rc::new(RefCell::new(cartridge)));
         self.reset();
+        Ok(())
     }
 
     pub fn reset(&mut self) {
@@ -38,19 +62,101 @@ impl Nes {
         }
     }
 
+    // Runs whole frames in real time
+    pub fn play(&mut self, dt: Duration) {
+        self.play_delta_time += dt.as_secs_f64();
+        let mut frames = 0;
+        while self.play_delta_time > NTSC_FRAME_LENGTH.as_secs_f64() {
+            self.play_delta_time -= NTSC_FRAME_LENGTH.as_secs_f64();
+            if frames < MAX_CATCH_UP_FRAMES {
+                self.frame();
+                frames += 1;
+            }
+        }
+    }
+
+    pub fn frame(&mut self) {
+        self.cpu.bus.ppu.frame_complete = false;
+        while !self.cpu.bus.ppu.frame_complete {
+            self.clock();
+        }
+    }
+
+    // Drains the audio of the finished frame, whichever mode the emulator runs in
+    fn end_frame(&mut self) {
+        let samples = self.audio.end_frame();
+        if let Some(dump) = &mut self.wav_dump {
+            if let Err(err) = dump.write(samples) {
+                println!("Error: Could not write WAV file: {}", err);
+                self.wav_dump = None;
+            }
+        }
+    }
+
+    // Records the audio of every following frame, independent of the audio device
+    pub fn start_wav_dump(&mut self, path: &str, per_channel: bool) -> io::Result<()> {
+        self.stop_wav_dump();
+        self.wav_dump = Some(WavDump::create(
+            path,
+            constants::audio::SAMPLE_RATE,
+            per_channel,
+        )?);
+        Ok(())
+    }
+
+    pub fn stop_wav_dump(&mut self) {
+        if let Some(dump) = self.wav_dump.take() {
+            if let Err(err) = dump.finish() {
+                println!("Error: Could not write WAV file: {}", err);
+            }
+        }
+    }
+
+    // Flushes battery-backed RAM every few seconds so a crash loses little progress
+    pub fn update_save(&mut self, dt: Duration) {
+        self.save_delta_time += dt.as_secs_f64();
+        if self.save_delta_time > SAVE_INTERVAL.as_secs_f64() {
+            self.flush_save();
+            self.save_delta_time = 0.0;
+        }
+    }
+
+    pub fn flush_save(&mut self) {
+        if let Err(err) = self.cpu.bus.get_cartridge().borrow_mut().flush_save() {
+            println!("Error: Could not write save file: {}", err);
+        }
+    }
+
     pub fn step(&mut self) {
-        while self.cpu.cycles == 0 {
+        while self.cpu.step == 0 {
             self.clock();
         }
-        while self.cpu.cycles != 0 {
+        while self.cpu.step != 0 {
             self.clock();
         }
     }
 
     pub fn clock(&mut self) {
         self.cpu.clock_ppu();
+        if self.cpu.bus.ppu.frame_ended() {
+            self.end_frame();
+        }
         if self.clock_counter % 3 == 0 {
-            self.cpu.clock();
+            // DMA can only halt the CPU on a read cycle
+            let dma = &self.cpu.bus.dma;
+            if dma.active() && (dma.halted() || self.cpu.next_cycle_reads()) {
+                self.cpu.bus.dma_cycle();
+            } else {
+                self.cpu.clock();
+            }
+            self.cpu.bus.clock();
+
+            let output = self.cpu.bus.apu.channel_output();
+            let expansion = self.cpu.bus.get_cartridge().borrow().audio_output();
+            self.audio.clock(output, expansion);
+            if let Some(dump) = &mut self.wav_dump {
+                dump.clock(output);
+            }
         }
         self.clock_counter += 1;
     }
@@ -0,0 +1,45 @@
+// Command line: <rom> [--wav <file>] [--wav-channels] [--headless <frames>]
+pub struct Options {
+    pub rom_file: String,
+    // Records the audio output into a WAV file
+    pub wav_file: Option<String>,
+    // Adds the individual APU channels next to the mixed output
+    pub wav_channels: bool,
+    // Runs the given number of frames without a window or audio device
+    pub headless_frames: Option<u64>,
+}
+
+impl Options {
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+        let mut rom_file = None;
+        let mut wav_file = None;
+        let mut wav_channels = false;
+        let mut headless_frames = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--wav" => {
+                    wav_file = Some(args.next().ok_or("--wav needs a file path")?);
+                }
+                "--wav-channels" => wav_channels = true,
+                "--headless" => {
+                    let frames = args.next().ok_or("--headless needs a frame count")?;
+                    headless_frames = Some(
+                        frames
+                            .parse()
+                            .map_err(|_| format!("Invalid frame count '{}'", frames))?,
+                    );
+                }
+                _ if rom_file.is_none() && !arg.starts_with("--") => rom_file = Some(arg),
+                _ => return Err(format!("Unexpected argument '{}'", arg)),
+            }
+        }
+
+        Ok(Options {
+            rom_file: rom_file.ok_or("Provide Path to ROM as Argument!")?,
+            wav_file,
+            wav_channels,
+            headless_frames,
+        })
+    }
+}
@@ -0,0 +1,60 @@
+// Internal PPU address register layout (v and t):
+// yyy NN YYYYY XXXXX
+// ||| || ||||| +++++-- coarse X scroll
+// ||| || +++++-------- coarse Y scroll
+// ||| ++-------------- nametable select
+// +++----------------- fine Y scroll
+#[derive(Clone, Copy)]
+pub struct LoopyRegister(pub u16);
+
+impl LoopyRegister {
+    const COARSE_X: u16 = 0x001F;
+    const COARSE_Y: u16 = 0x03E0;
+    const NAMETABLE_X: u16 = 0x0400;
+    const NAMETABLE_Y: u16 = 0x0800;
+    const FINE_Y: u16 = 0x7000;
+
+    pub fn new() -> LoopyRegister {
+        LoopyRegister(0x0000)
+    }
+
+    pub fn coarse_x(&self) -> u16 {
+        self.0 & Self::COARSE_X
+    }
+
+    pub fn set_coarse_x(&mut self, value: u16) {
+        self.0 = (self.0 & !Self::COARSE_X) | (value & 0x1F);
+    }
+
+    pub fn coarse_y(&self) -> u16 {
+        (self.0 & Self::COARSE_Y) >> 5
+    }
+
+    pub fn set_coarse_y(&mut self, value: u16) {
+        self.0 = (self.0 & !Self::COARSE_Y) | ((value & 0x1F) << 5);
+    }
+
+    pub fn nametable_x(&self) -> u16 {
+        (self.0 & Self::NAMETABLE_X) >> 10
+    }
+
+    pub fn set_nametable_x(&mut self, value: u16) {
+        self.0 = (self.0 & !Self::NAMETABLE_X) | ((value & 0x01) << 10);
+    }
+
+    pub fn nametable_y(&self) -> u16 {
+        (self.0 & Self::NAMETABLE_Y) >> 11
+    }
+
+    pub fn set_nametable_y(&mut self, value: u16) {
+        self.0 = (self.0 & !Self::NAMETABLE_Y) | ((value & 0x01) << 11);
+    }
+
+    pub fn fine_y(&self) -> u16 {
+        (self.0 & Self::FINE_Y) >> 12
+    }
+
+    pub fn set_fine_y(&mut self, value: u16) {
+        self.0 = (self.0 & !Self::FINE_Y) | ((value & 0x07) << 12);
+    }
+}
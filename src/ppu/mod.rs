@@ -1 +1,2 @@
+mod loopy_register;
 pub mod ppu;
@@ -2,50 +2,337 @@ use std::{cell::RefCell, rc::Rc};
 
 use palette::Srgb;
 
-use crate::{bus::ppu_bus::PpuBus, cartridge::cartridge::Cartridge, ram::ppu_ram::PpuStatusFlag};
+use crate::{
+    bus::ppu_bus::PpuBus,
+    cartridge::cartridge::Cartridge,
+    constants,
+    ram::ppu_ram::{PpuAddr, PpuControlFlag, PpuMaskFlag, PpuStatusFlag},
+};
+
+use super::loopy_register::LoopyRegister;
 
 pub struct Ppu {
-    #[allow(unused)]
     colors: Vec<Srgb<u8>>,
     screen: Vec<Srgb<u8>>,
 
-    frame_complete: bool,
+    pub frame_complete: bool,
+    pub nmi: bool,
+    nmi_output: bool,
+    suppress_vblank: bool,
+    odd_frame: bool,
     scanline: i32,
     cycle: i32,
 
+    // Internal Registers
+    vram_addr: LoopyRegister,
+    tram_addr: LoopyRegister,
+    fine_x: u8,
+    address_latch: bool,
+    data_buffer: u8,
+    open_bus: u8,
+
+    // Background Fetches
+    bg_next_tile_id: u8,
+    bg_next_tile_attrib: u8,
+    bg_next_tile_lsb: u8,
+    bg_next_tile_msb: u8,
+
+    // Background Shifters
+    bg_shifter_pattern_lo: u16,
+    bg_shifter_pattern_hi: u16,
+    bg_shifter_attrib_lo: u16,
+    bg_shifter_attrib_hi: u16,
+
+    // Object Attribute Memory
+    pub oam: [u8; 256],
+    oam_addr: u8,
+    secondary_oam: [u8; 32],
+
+    // Sprites of the current scanline
+    sprite_count: usize,
+    sprite_x: [u8; 8],
+    sprite_attrib: [u8; 8],
+    sprite_shifter_pattern_lo: [u8; 8],
+    sprite_shifter_pattern_hi: [u8; 8],
+    sprite_zero_hit_possible: bool,
+    sprite_zero_on_line: bool,
+
     pub bus: Box<PpuBus>,
 }
 
 impl Ppu {
     pub fn new(cartridge: Rc<RefCell<Cartridge>>) -> Ppu {
         let ppu: Ppu = Ppu {
-            colors: vec![Srgb::<u8>::new(0, 0, 0); 0x40],
-            screen: vec![Srgb::<u8>::new(0, 0, 0); 256 * 240],
+            colors: constants::ppu::PALETTE
+                .iter()
+                .map(|&(r, g, b)| Srgb::<u8>::new(r, g, b))
+                .collect(),
+            screen: vec![
+                Srgb::<u8>::new(0, 0, 0);
+                constants::ppu::SCREEN_WIDTH * constants::ppu::SCREEN_HEIGHT
+            ],
             frame_complete: false,
+            nmi: false,
+            nmi_output: false,
+            suppress_vblank: false,
+            odd_frame: false,
             scanline: 0,
             cycle: 0,
+            vram_addr: LoopyRegister::new(),
+            tram_addr: LoopyRegister::new(),
+            fine_x: 0x00,
+            address_latch: false,
+            data_buffer: 0x00,
+            open_bus: 0x00,
+            bg_next_tile_id: 0x00,
+            bg_next_tile_attrib: 0x00,
+            bg_next_tile_lsb: 0x00,
+            bg_next_tile_msb: 0x00,
+            bg_shifter_pattern_lo: 0x0000,
+            bg_shifter_pattern_hi: 0x0000,
+            bg_shifter_attrib_lo: 0x0000,
+            bg_shifter_attrib_hi: 0x0000,
+            oam: [0x00; 256],
+            oam_addr: 0x00,
+            secondary_oam: [0xFF; 32],
+            sprite_count: 0,
+            sprite_x: [0x00; 8],
+            sprite_attrib: [0x00; 8],
+            sprite_shifter_pattern_lo: [0x00; 8],
+            sprite_shifter_pattern_hi: [0x00; 8],
+            sprite_zero_hit_possible: false,
+            sprite_zero_on_line: false,
             bus: Box::new(PpuBus::new(cartridge)),
         };
         ppu
     }
 
+    pub fn cpu_read(&mut self, addr: u16, readonly: bool) -> u8 {
+        if readonly {
+            // Inspect registers without any of the read side effects
+            return match PpuAddr::from(addr) {
+                PpuAddr::Control => self.bus.ram.get_control(),
+                PpuAddr::Mask => self.bus.ram.get_mask(),
+                PpuAddr::Status => self.bus.ram.get_status(),
+                PpuAddr::OAMData => self.oam[self.oam_addr as usize],
+                PpuAddr::PPUData => self.data_buffer,
+                _ => 0x00,
+            };
+        }
+
+        match PpuAddr::from(addr) {
+            PpuAddr::Status => {
+                if self.scanline == 241 && self.cycle == 1 {
+                    // Reading just before vblank starts hides the flag and the NMI for this frame
+                    self.suppress_vblank = true;
+                } else if self.scanline == 241 && (self.cycle == 2 || self.cycle == 3) {
+                    // Reading right as vblank starts returns the flag but cancels the NMI
+                    self.nmi = false;
+                }
+
+                // Only the upper 3 bits are driven, the rest is stale bus content
+                self.open_bus = (self.bus.ram.get_status() & 0xE0) | (self.open_bus & 0x1F);
+                self.bus
+                    .ram
+                    .set_status_flag(PpuStatusFlag::VERTICAL_BLANK, false);
+                self.address_latch = false;
+                self.update_nmi();
+            }
+            PpuAddr::OAMData => {
+                self.open_bus = self.oam[self.oam_addr as usize];
+                if (self.oam_addr & 0x03) == 0x02 {
+                    // Unimplemented attribute bits always read back as 0
+                    self.open_bus &= 0xE3;
+                }
+            }
+            PpuAddr::PPUData => {
+                // Reads are delayed by one through the internal buffer
+                let addr = self.ppu_data_addr();
+                let bus = self.open_bus;
+                self.open_bus = self.data_buffer;
+                self.data_buffer = self.bus.read(addr, false);
+
+                // Except for the palette, which is returned immediately while the buffer
+                // is filled with the nametable data "underneath" it. Palette entries are
+                // only 6 bits, the rest comes from the open bus
+                if addr >= constants::ppu::PALETTE_ADDR {
+                    let palette = self.data_buffer;
+                    self.open_bus = (palette & 0x3F) | (bus & 0xC0);
+                    self.data_buffer = self.bus.read(addr - 0x1000, false);
+                }
+                self.increment_vram_addr();
+            }
+            _ => {}
+        }
+        self.open_bus
+    }
+
+    pub fn cpu_write(&mut self, addr: u16, data: u8) {
+        self.open_bus = data;
+        match PpuAddr::from(addr) {
+            PpuAddr::Control => {
+                self.bus.ram.set_control(data);
+                self.tram_addr.set_nametable_x(
+                    self.bus.ram.get_control_flag(PpuControlFlag::NAMETABLE_X) as u16,
+                );
+                self.tram_addr.set_nametable_y(
+                    self.bus.ram.get_control_flag(PpuControlFlag::NAMETABLE_Y) as u16,
+                );
+                // Enabling NMIs during vblank immediately fires one
+                self.update_nmi();
+            }
+            PpuAddr::Mask => {
+                self.bus.ram.set_mask(data);
+            }
+            PpuAddr::OAMAddr => {
+                self.oam_addr = data;
+            }
+            PpuAddr::OAMData => {
+                self.oam[self.oam_addr as usize] = data;
+                self.oam_addr = self.oam_addr.wrapping_add(1);
+            }
+            PpuAddr::Scroll => {
+                if !self.address_latch {
+                    self.fine_x = data & 0x07;
+                    self.tram_addr.set_coarse_x((data >> 3) as u16);
+                } else {
+                    self.tram_addr.set_fine_y((data & 0x07) as u16);
+                    self.tram_addr.set_coarse_y((data >> 3) as u16);
+                }
+                self.address_latch = !self.address_latch;
+            }
+            PpuAddr::PPUAddr => {
+                if !self.address_latch {
+                    // Upper 6 bits, bit 14 is cleared
+                    self.tram_addr.0 = (((data & 0x3F) as u16) << 8) | (self.tram_addr.0 & 0x00FF);
+                } else {
+                    self.tram_addr.0 = (self.tram_addr.0 & 0xFF00) | data as u16;
+                    self.vram_addr = self.tram_addr;
+                }
+                self.address_latch = !self.address_latch;
+            }
+            PpuAddr::PPUData => {
+                self.bus.write(self.ppu_data_addr(), data);
+                self.increment_vram_addr();
+            }
+            PpuAddr::Status | PpuAddr::Invalid => {}
+        }
+    }
+
+    // True for the first dot after the last one of a frame, unlike frame_complete
+    // it doesn't depend on anyone acknowledging the frame
+    pub fn frame_ended(&self) -> bool {
+        self.scanline == -1 && self.cycle == 0
+    }
+
+    // v is 15 bits wide, but only 14 of them reach the PPU address bus
+    fn ppu_data_addr(&self) -> u16 {
+        self.vram_addr.0 & 0x3FFF
+    }
+
     pub fn clock(&mut self) {
-        if self.scanline == -1 && self.cycle == 1 {
-            self.bus
-                .ram
-                .set_status_flag(PpuStatusFlag::VERTICAL_BLANK, false);
+        if self.scanline >= -1 && self.scanline < 240 {
+            // The idle cycle of the first visible scanline is skipped on odd frames
+            if self.scanline == 0 && self.cycle == 0 && self.odd_frame && self.rendering_enabled() {
+                self.cycle = 1;
+            }
+
+            if self.scanline == -1 && self.cycle == 1 {
+                self.bus
+                    .ram
+                    .set_status_flag(PpuStatusFlag::VERTICAL_BLANK, false);
+                self.bus
+                    .ram
+                    .set_status_flag(PpuStatusFlag::SPRITE_OVERFLOW, false);
+                self.bus
+                    .ram
+                    .set_status_flag(PpuStatusFlag::SPRITE_ZERO_HIT, false);
+                self.update_nmi();
+            }
+
+            // Nothing is fetched while rendering is off, so the mapper sees no A12 edges
+            let fetching = self.rendering_enabled();
+
+            if fetching
+                && ((self.cycle >= 2 && self.cycle < 258)
+                    || (self.cycle >= 321 && self.cycle < 338))
+            {
+                self.update_shifters();
+
+                match (self.cycle - 1) % 8 {
+                    0 => {
+                        self.load_background_shifters();
+                        self.fetch_tile_id();
+                    }
+                    2 => {
+                        self.fetch_tile_attrib();
+                    }
+                    4 => {
+                        self.bg_next_tile_lsb = self.bus.read(self.pattern_addr(), false);
+                    }
+                    6 => {
+                        self.bg_next_tile_msb = self.bus.read(self.pattern_addr() + 8, false);
+                    }
+                    7 => {
+                        self.increment_scroll_x();
+                    }
+                    _ => {}
+                }
+            }
+
+            if self.cycle == 256 {
+                self.increment_scroll_y();
+            }
+
+            if self.cycle == 257 {
+                self.load_background_shifters();
+                self.transfer_address_x();
+            }
+
+            // Unused nametable fetches at the end of the scanline
+            if fetching && (self.cycle == 338 || self.cycle == 340) {
+                self.fetch_tile_id();
+            }
+
+            if self.scanline == -1 && self.cycle >= 280 && self.cycle < 305 {
+                self.transfer_address_y();
+            }
+
+            // Sprites for the next scanline are evaluated and fetched at the end of this one
+            if self.cycle == 257 {
+                self.evaluate_sprites();
+            }
+
+            if fetching && self.cycle >= 257 && self.cycle <= 320 {
+                self.oam_addr = 0x00;
+                let slot = ((self.cycle - 257) / 8) as usize;
+                match (self.cycle - 257) % 8 {
+                    5 => self.fetch_sprite_pattern(slot, false),
+                    7 => self.fetch_sprite_pattern(slot, true),
+                    _ => {}
+                }
+            }
         }
+
         if self.scanline == 241 && self.cycle == 1 {
-            self.bus
-                .ram
-                .set_status_flag(PpuStatusFlag::VERTICAL_BLANK, true);
-            // if (bus.ppu.control.enable_nmi) {
-            //     nmi = true;
-            // }
+            if !self.suppress_vblank {
+                self.bus
+                    .ram
+                    .set_status_flag(PpuStatusFlag::VERTICAL_BLANK, true);
+                self.update_nmi();
+            }
+            self.suppress_vblank = false;
+        }
+
+        // Pixels are only output on the visible part of the frame
+        if self.scanline >= 0
+            && (self.scanline as usize) < constants::ppu::SCREEN_HEIGHT
+            && self.cycle >= 1
+            && (self.cycle as usize) <= constants::ppu::SCREEN_WIDTH
+        {
+            self.render_pixel();
         }
 
-        // ! TODO: FIX BUG WITH SCREEN VEC OVERFLOW!
-        self.screen[((self.cycle) + self.scanline * 240) as usize] = Srgb::<u8>::new(0, 255, 0);
         self.cycle += 1;
         if self.cycle >= 341 {
             // Scan line length
@@ -55,8 +342,434 @@ impl Ppu {
         if self.scanline >= 261 {
             self.scanline = -1;
             self.frame_complete = true;
+            self.odd_frame = !self.odd_frame;
+        }
+    }
+
+    pub fn get_screen(&self) -> &[Srgb<u8>] {
+        &self.screen
+    }
+
+    pub fn get_color_from_palette(&self, palette: u8, pixel: u8) -> Srgb<u8> {
+        let addr = constants::ppu::PALETTE_ADDR + ((palette as u16) << 2) + pixel as u16;
+        self.colors[(self.bus.read(addr, false) & 0x3F) as usize]
+    }
+
+    // The NMI line is the AND of the vblank flag and the enable bit,
+    // the CPU reacts to its rising edge
+    fn update_nmi(&mut self) {
+        let output = self.bus.ram.get_status_flag(PpuStatusFlag::VERTICAL_BLANK)
+            && self.bus.ram.get_control_flag(PpuControlFlag::ENABLE_NMI);
+        if output && !self.nmi_output {
+            self.nmi = true;
+        }
+        self.nmi_output = output;
+    }
+
+    fn increment_vram_addr(&mut self) {
+        if self
+            .bus
+            .ram
+            .get_control_flag(PpuControlFlag::INCREMENT_MODE)
+        {
+            self.vram_addr.0 += 32;
+        } else {
+            self.vram_addr.0 += 1;
+        }
+        self.vram_addr.0 &= 0x7FFF;
+    }
+
+    fn rendering_enabled(&self) -> bool {
+        self.bus.ram.get_mask_flag(PpuMaskFlag::RENDER_BACKGROUND)
+            || self.bus.ram.get_mask_flag(PpuMaskFlag::RENDER_SPRITES)
+    }
+
+    fn fetch_tile_id(&mut self) {
+        self.bg_next_tile_id = self.bus.read(
+            constants::ppu::NAMETABLE_ADDR | (self.vram_addr.0 & 0x0FFF),
+            false,
+        );
+    }
+
+    fn fetch_tile_attrib(&mut self) {
+        let addr = constants::ppu::ATTRIBUTE_ADDR
+            | (self.vram_addr.nametable_y() << 11)
+            | (self.vram_addr.nametable_x() << 10)
+            | ((self.vram_addr.coarse_y() >> 2) << 3)
+            | (self.vram_addr.coarse_x() >> 2);
+        self.bg_next_tile_attrib = self.bus.read(addr, false);
+
+        // Select the 2 bit palette of the 2x2 tile quadrant
+        if (self.vram_addr.coarse_y() & 0x02) != 0 {
+            self.bg_next_tile_attrib >>= 4;
+        }
+        if (self.vram_addr.coarse_x() & 0x02) != 0 {
+            self.bg_next_tile_attrib >>= 2;
+        }
+        self.bg_next_tile_attrib &= 0x03;
+    }
+
+    fn pattern_addr(&self) -> u16 {
+        let table = self
+            .bus
+            .ram
+            .get_control_flag(PpuControlFlag::PATTERN_BACKGROUND) as u16;
+        (table << 12) + ((self.bg_next_tile_id as u16) << 4) + self.vram_addr.fine_y()
+    }
+
+    fn increment_scroll_x(&mut self) {
+        if !self.rendering_enabled() {
+            return;
+        }
+        if self.vram_addr.coarse_x() == 31 {
+            self.vram_addr.set_coarse_x(0);
+            self.vram_addr
+                .set_nametable_x(!self.vram_addr.nametable_x() & 0x01);
+        } else {
+            self.vram_addr.set_coarse_x(self.vram_addr.coarse_x() + 1);
+        }
+    }
+
+    fn increment_scroll_y(&mut self) {
+        if !self.rendering_enabled() {
+            return;
+        }
+        if self.vram_addr.fine_y() < 7 {
+            self.vram_addr.set_fine_y(self.vram_addr.fine_y() + 1);
+            return;
+        }
+
+        self.vram_addr.set_fine_y(0);
+        match self.vram_addr.coarse_y() {
+            29 => {
+                // Last row of the nametable, the rest are attributes
+                self.vram_addr.set_coarse_y(0);
+                self.vram_addr
+                    .set_nametable_y(!self.vram_addr.nametable_y() & 0x01);
+            }
+            31 => {
+                // Pointer is in attribute memory, wrap without switching nametables
+                self.vram_addr.set_coarse_y(0);
+            }
+            coarse_y => {
+                self.vram_addr.set_coarse_y(coarse_y + 1);
+            }
+        }
+    }
+
+    fn transfer_address_x(&mut self) {
+        if !self.rendering_enabled() {
+            return;
+        }
+        self.vram_addr.set_nametable_x(self.tram_addr.nametable_x());
+        self.vram_addr.set_coarse_x(self.tram_addr.coarse_x());
+    }
+
+    fn transfer_address_y(&mut self) {
+        if !self.rendering_enabled() {
+            return;
+        }
+        self.vram_addr.set_fine_y(self.tram_addr.fine_y());
+        self.vram_addr.set_nametable_y(self.tram_addr.nametable_y());
+        self.vram_addr.set_coarse_y(self.tram_addr.coarse_y());
+    }
+
+    fn load_background_shifters(&mut self) {
+        self.bg_shifter_pattern_lo =
+            (self.bg_shifter_pattern_lo & 0xFF00) | self.bg_next_tile_lsb as u16;
+        self.bg_shifter_pattern_hi =
+            (self.bg_shifter_pattern_hi & 0xFF00) | self.bg_next_tile_msb as u16;
+
+        // Attributes are constant for the whole tile, so inflate them to 8 bits
+        let attrib_lo: u16 = if (self.bg_next_tile_attrib & 0x01) != 0 {
+            0x00FF
+        } else {
+            0x0000
+        };
+        let attrib_hi: u16 = if (self.bg_next_tile_attrib & 0x02) != 0 {
+            0x00FF
+        } else {
+            0x0000
+        };
+        self.bg_shifter_attrib_lo = (self.bg_shifter_attrib_lo & 0xFF00) | attrib_lo;
+        self.bg_shifter_attrib_hi = (self.bg_shifter_attrib_hi & 0xFF00) | attrib_hi;
+    }
+
+    // Muxes the background and sprite pixel at the current dot into the frame
+    fn render_pixel(&mut self) {
+        let mut bg_pixel: u8 = 0x00;
+        let mut bg_palette: u8 = 0x00;
+
+        if self.bus.ram.get_mask_flag(PpuMaskFlag::RENDER_BACKGROUND)
+            && (self.cycle > 8
+                || self
+                    .bus
+                    .ram
+                    .get_mask_flag(PpuMaskFlag::RENDER_BACKGROUND_LEFT))
+        {
+            let bit_mux: u16 = 0x8000 >> self.fine_x;
+
+            let p0_pixel = ((self.bg_shifter_pattern_lo & bit_mux) > 0) as u8;
+            let p1_pixel = ((self.bg_shifter_pattern_hi & bit_mux) > 0) as u8;
+            bg_pixel = (p1_pixel << 1) | p0_pixel;
+
+            let bg_pal0 = ((self.bg_shifter_attrib_lo & bit_mux) > 0) as u8;
+            let bg_pal1 = ((self.bg_shifter_attrib_hi & bit_mux) > 0) as u8;
+            bg_palette = (bg_pal1 << 1) | bg_pal0;
+        }
+
+        let mut fg_pixel: u8 = 0x00;
+        let mut fg_palette: u8 = 0x00;
+        let mut fg_priority: bool = false;
+        let mut sprite_zero_rendered: bool = false;
+
+        if self.bus.ram.get_mask_flag(PpuMaskFlag::RENDER_SPRITES)
+            && (self.cycle > 8 || self.bus.ram.get_mask_flag(PpuMaskFlag::RENDER_SPRITES_LEFT))
+        {
+            // Sprites are ordered by priority, the first opaque one wins
+            for i in 0..self.sprite_count {
+                if self.sprite_x[i] != 0 {
+                    continue;
+                }
+                let p0_pixel = ((self.sprite_shifter_pattern_lo[i] & 0x80) > 0) as u8;
+                let p1_pixel = ((self.sprite_shifter_pattern_hi[i] & 0x80) > 0) as u8;
+                let pixel = (p1_pixel << 1) | p0_pixel;
+                if pixel == 0x00 {
+                    continue;
+                }
+
+                fg_pixel = pixel;
+                fg_palette = (self.sprite_attrib[i] & 0x03) + 0x04;
+                fg_priority = (self.sprite_attrib[i] & 0x20) == 0;
+                sprite_zero_rendered = i == 0 && self.sprite_zero_on_line;
+                break;
+            }
+        }
+
+        if sprite_zero_rendered && bg_pixel != 0x00 && self.cycle != 256 {
+            // Both layers have to be enabled, which is already implied by the opaque pixels
+            self.bus
+                .ram
+                .set_status_flag(PpuStatusFlag::SPRITE_ZERO_HIT, true);
+        }
+
+        let (pixel, palette) = match (bg_pixel, fg_pixel) {
+            (0, 0) => (0x00, 0x00),
+            (0, _) => (fg_pixel, fg_palette),
+            (_, 0) => (bg_pixel, bg_palette),
+            _ if fg_priority => (fg_pixel, fg_palette),
+            _ => (bg_pixel, bg_palette),
+        };
+
+        let x = (self.cycle - 1) as usize;
+        let index = self.scanline as usize * constants::ppu::SCREEN_WIDTH + x;
+        self.screen[index] = self.get_color_from_palette(palette, pixel);
+    }
+
+    fn update_shifters(&mut self) {
+        if self.bus.ram.get_mask_flag(PpuMaskFlag::RENDER_BACKGROUND) {
+            self.bg_shifter_pattern_lo <<= 1;
+            self.bg_shifter_pattern_hi <<= 1;
+            self.bg_shifter_attrib_lo <<= 1;
+            self.bg_shifter_attrib_hi <<= 1;
+        }
+
+        if self.bus.ram.get_mask_flag(PpuMaskFlag::RENDER_SPRITES) && self.cycle < 257 {
+            for i in 0..self.sprite_count {
+                if self.sprite_x[i] > 0 {
+                    self.sprite_x[i] -= 1;
+                } else {
+                    self.sprite_shifter_pattern_lo[i] <<= 1;
+                    self.sprite_shifter_pattern_hi[i] <<= 1;
+                }
+            }
+        }
+    }
+
+    fn sprite_height(&self) -> i32 {
+        if self.bus.ram.get_control_flag(PpuControlFlag::SPRITE_SIZE) {
+            16
+        } else {
+            8
+        }
+    }
+
+    fn evaluate_sprites(&mut self) {
+        self.secondary_oam = [0xFF; 32];
+        self.sprite_zero_hit_possible = false;
+
+        // Nothing is evaluated on the pre-render scanline
+        if self.scanline < 0 || !self.rendering_enabled() {
+            self.sprite_count = 0;
+            return;
+        }
+
+        let height = self.sprite_height();
+        let in_range = |y: u8| {
+            let diff = self.scanline - y as i32;
+            diff >= 0 && diff < height
+        };
+
+        let mut found: usize = 0;
+        let mut n: usize = 0;
+        while n < 64 && found < 8 {
+            if in_range(self.oam[n * 4]) {
+                self.secondary_oam[found * 4..found * 4 + 4]
+                    .copy_from_slice(&self.oam[n * 4..n * 4 + 4]);
+                if n == 0 {
+                    self.sprite_zero_hit_possible = true;
+                }
+                found += 1;
+            }
+            n += 1;
+        }
+
+        // Hardware bug: once secondary OAM is full the byte offset is incremented
+        // alongside the sprite index, so other bytes are wrongly treated as Y
+        let mut m: usize = 0;
+        while n < 64 {
+            if in_range(self.oam[n * 4 + m]) {
+                self.bus
+                    .ram
+                    .set_status_flag(PpuStatusFlag::SPRITE_OVERFLOW, true);
+                break;
+            }
+            n += 1;
+            m = (m + 1) & 0x03;
+        }
+
+        self.sprite_count = found;
+    }
+
+    fn fetch_sprite_pattern(&mut self, slot: usize, high: bool) {
+        let y = self.secondary_oam[slot * 4];
+        let tile = self.secondary_oam[slot * 4 + 1];
+        let attrib = self.secondary_oam[slot * 4 + 2];
+        let x = self.secondary_oam[slot * 4 + 3];
+
+        let mut row = (self.scanline - y as i32) as u16;
+        let height = self.sprite_height() as u16;
+        if (attrib & 0x80) != 0 {
+            // Flipped vertically
+            row = (height - 1).wrapping_sub(row);
+        }
+
+        let addr: u16 = if height == 8 {
+            let table = self
+                .bus
+                .ram
+                .get_control_flag(PpuControlFlag::PATTERN_SPRITE) as u16;
+            (table << 12) | ((tile as u16) << 4) | (row & 0x07)
+        } else {
+            // 8x16 sprites select the pattern table with bit 0 of the tile index
+            let table = (tile & 0x01) as u16;
+            let tile = ((tile & 0xFE) as u16) + ((row >> 3) & 0x01);
+            (table << 12) | (tile << 4) | (row & 0x07)
+        };
+
+        // Empty slots still perform a dummy fetch
+        let mut data = self.bus.read(if high { addr + 8 } else { addr }, false);
+        if slot >= self.sprite_count {
+            return;
+        }
+        if (attrib & 0x40) != 0 {
+            // Flipped horizontally
+            data = data.reverse_bits();
+        }
+
+        if high {
+            self.sprite_shifter_pattern_hi[slot] = data;
+        } else {
+            self.sprite_shifter_pattern_lo[slot] = data;
+            self.sprite_x[slot] = x;
+            self.sprite_attrib[slot] = attrib;
+            if slot == 0 {
+                self.sprite_zero_on_line = self.sprite_zero_hit_possible;
+            }
         }
     }
 
     // TODO: GetPatternTable
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendering_ppu() -> Ppu {
+        let mut ppu = Ppu::new(Rc::new(RefCell::new(Cartridge::new())));
+        ppu.cpu_write(constants::ppu::MASK_ADDR, 0x18);
+        ppu
+    }
+
+    #[test]
+    fn coarse_x_wraps_into_next_nametable() {
+        let mut ppu = rendering_ppu();
+        ppu.vram_addr.set_coarse_x(31);
+
+        ppu.increment_scroll_x();
+        assert_eq!(ppu.vram_addr.coarse_x(), 0);
+        assert_eq!(ppu.vram_addr.nametable_x(), 1);
+
+        ppu.vram_addr.set_coarse_x(31);
+        ppu.increment_scroll_x();
+        assert_eq!(ppu.vram_addr.nametable_x(), 0);
+    }
+
+    #[test]
+    fn fine_y_carries_into_coarse_y() {
+        let mut ppu = rendering_ppu();
+        ppu.vram_addr.set_fine_y(6);
+        ppu.vram_addr.set_coarse_y(4);
+
+        ppu.increment_scroll_y();
+        assert_eq!(ppu.vram_addr.fine_y(), 7);
+        assert_eq!(ppu.vram_addr.coarse_y(), 4);
+
+        ppu.increment_scroll_y();
+        assert_eq!(ppu.vram_addr.fine_y(), 0);
+        assert_eq!(ppu.vram_addr.coarse_y(), 5);
+    }
+
+    #[test]
+    fn coarse_y_wraps_at_row_29_and_31() {
+        let mut ppu = rendering_ppu();
+        ppu.vram_addr.set_fine_y(7);
+        ppu.vram_addr.set_coarse_y(29);
+        ppu.increment_scroll_y();
+        assert_eq!(ppu.vram_addr.coarse_y(), 0);
+        assert_eq!(ppu.vram_addr.nametable_y(), 1);
+
+        // Rows 30 and 31 are attribute memory, leaving them doesn't switch nametables
+        ppu.vram_addr.set_fine_y(7);
+        ppu.vram_addr.set_coarse_y(31);
+        ppu.increment_scroll_y();
+        assert_eq!(ppu.vram_addr.coarse_y(), 0);
+        assert_eq!(ppu.vram_addr.nametable_y(), 1);
+    }
+
+    #[test]
+    fn transfers_copy_only_their_axis() {
+        let mut ppu = rendering_ppu();
+        ppu.tram_addr = LoopyRegister(0x7FFF);
+
+        ppu.transfer_address_x();
+        assert_eq!(ppu.vram_addr.0, 0x041F);
+
+        ppu.transfer_address_y();
+        assert_eq!(ppu.vram_addr.0, 0x7FFF);
+    }
+
+    #[test]
+    fn scroll_is_frozen_without_rendering() {
+        let mut ppu = rendering_ppu();
+        ppu.cpu_write(constants::ppu::MASK_ADDR, 0x00);
+        ppu.vram_addr.set_coarse_x(31);
+        ppu.tram_addr = LoopyRegister(0x7FFF);
+
+        ppu.increment_scroll_x();
+        ppu.increment_scroll_y();
+        ppu.transfer_address_y();
+        assert_eq!(ppu.vram_addr.0, 0x001F);
+    }
+}
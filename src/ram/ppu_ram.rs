@@ -1,6 +1,6 @@
 use bitflags::bitflags;
 
-use crate::constants;
+use crate::{cartridge::mirror::Mirror, constants};
 
 pub enum PpuAddr {
     Control,
@@ -35,9 +35,6 @@ pub struct PpuRAM {
     status: u8,
     mask: u8,
     control: u8,
-    address_latch: u8,
-    data_buffer: u8,
-    ppu_address: u16,
     name_table: [[u8; 1024]; 2],
     palette_table: [u8; 32],
     pattern_table: [[u8; 4096]; 2],
@@ -49,9 +46,6 @@ impl PpuRAM {
             status: 0,
             mask: 0,
             control: 0,
-            address_latch: 0x00,
-            data_buffer: 0x00,
-            ppu_address: 0x0000,
             name_table: [[0; 1024]; 2],
             palette_table: [0; 32],
             pattern_table: [[0; 4096]; 2],
@@ -60,14 +54,17 @@ impl PpuRAM {
 }
 
 impl PpuRAM {
-    pub fn read(&self, addr: u16, _readonly: bool) -> u8 {
+    pub fn read(&self, addr: u16, mirror: Mirror, _readonly: bool) -> u8 {
         let mut index: usize = (addr as usize) & 0x3FFF;
         if addr <= 0x1FFF {
             // Pattern Memory
             let index_u: usize = ((addr as usize) & 0x1000) >> 12;
             let index_l: usize = (addr as usize) & 0x0FFF;
             return self.pattern_table[index_u][index_l];
-        } else if addr >= 0x2000 && addr <= 0x3EFF { // Nametable Memory
+        } else if addr >= 0x2000 && addr <= 0x3EFF {
+            // Nametable Memory
+            let (table, index) = PpuRAM::name_table_index(addr, mirror);
+            return self.name_table[table][index];
         } else if addr >= 0x3F00 && addr <= 0x3FFF {
             // Palette Memory
             index &= 0x001F;
@@ -88,7 +85,7 @@ impl PpuRAM {
         return 0;
     }
 
-    pub fn write(&mut self, addr: u16, data: u8) {
+    pub fn write(&mut self, addr: u16, mirror: Mirror, data: u8) {
         let mut index: usize = (addr as usize) & 0x3FFF;
         if addr <= 0x1FFF {
             // Pattern Memory
@@ -96,7 +93,10 @@ impl PpuRAM {
             let index_u: usize = ((addr as usize) & 0x1000) >> 12;
             let index_l: usize = (addr as usize) & 0x0FFF;
             self.pattern_table[index_u][index_l] = data;
-        } else if addr >= 0x2000 && addr <= 0x3EFF { // Nametable Memory
+        } else if addr >= 0x2000 && addr <= 0x3EFF {
+            // Nametable Memory
+            let (table, index) = PpuRAM::name_table_index(addr, mirror);
+            self.name_table[table][index] = data;
         } else if addr >= 0x3F00 && addr <= 0x3FFF {
             // Palette Memory
             index &= 0x001F;
@@ -116,6 +116,52 @@ impl PpuRAM {
         }
     }
 
+    // Maps one of the four logical nametables onto the 2 KiB CIRAM
+    fn name_table_index(addr: u16, mirror: Mirror) -> (usize, usize) {
+        let addr = (addr as usize) & 0x0FFF;
+        let logical = addr >> 10;
+        let table = match mirror {
+            Mirror::Horizontal => logical >> 1,
+            Mirror::SingleScreenA => 0,
+            Mirror::SingleScreenB => 1,
+            // Four-screen boards only leave the first two tables to CIRAM
+            Mirror::Vertical | Mirror::FourScreen | Mirror::Hardware => logical & 0x01,
+        };
+        (table, addr & 0x03FF)
+    }
+
+    pub fn get_status(&self) -> u8 {
+        self.status
+    }
+
+    pub fn get_status_flag(&self, flag: PpuStatusFlag) -> bool {
+        (self.status & flag.bits()) != 0
+    }
+
+    pub fn get_control(&self) -> u8 {
+        self.control
+    }
+
+    pub fn set_control(&mut self, data: u8) {
+        self.control = data;
+    }
+
+    pub fn get_control_flag(&self, flag: PpuControlFlag) -> bool {
+        (self.control & flag.bits()) != 0
+    }
+
+    pub fn get_mask(&self) -> u8 {
+        self.mask
+    }
+
+    pub fn set_mask(&mut self, data: u8) {
+        self.mask = data;
+    }
+
+    pub fn get_mask_flag(&self, flag: PpuMaskFlag) -> bool {
+        (self.mask & flag.bits()) != 0
+    }
+
     pub fn set_status_flag(&mut self, flag: PpuStatusFlag, value: bool) {
         if value {
             self.status |= flag.bits();
@@ -0,0 +1,64 @@
+use ggez::{
+    glam::Vec2,
+    graphics::{Canvas, DrawParam, Image, ImageFormat, Sampler},
+    Context,
+};
+
+use crate::{constants, ppu::ppu::Ppu};
+
+pub struct GameView {
+    frame: Image,
+    pixels: Vec<u8>,
+}
+
+impl GameView {
+    pub fn new(ctx: &mut Context) -> GameView {
+        let pixels = vec![0xFF; constants::ppu::SCREEN_WIDTH * constants::ppu::SCREEN_HEIGHT * 4];
+        let frame = GameView::create_image(ctx, &pixels);
+        GameView { frame, pixels }
+    }
+
+    // Uploads the PPU screen once a frame has been fully rendered
+    pub fn update(&mut self, ctx: &mut Context, ppu: &mut Ppu) {
+        if !ppu.frame_complete {
+            return;
+        }
+        ppu.frame_complete = false;
+
+        for (pixel, color) in self.pixels.chunks_exact_mut(4).zip(ppu.get_screen()) {
+            pixel[0] = color.red;
+            pixel[1] = color.green;
+            pixel[2] = color.blue;
+            pixel[3] = 0xFF;
+        }
+        self.frame = GameView::create_image(ctx, &self.pixels);
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, dest: Vec2, scale: f32) {
+        // Keep the pixels sharp when scaling up
+        canvas.set_sampler(Sampler::nearest_clamp());
+        canvas.draw(
+            &self.frame,
+            DrawParam::new().dest(dest).scale(Vec2::new(scale, scale)),
+        );
+        canvas.set_sampler(Sampler::default());
+    }
+
+    pub fn width(scale: f32) -> f32 {
+        constants::ppu::SCREEN_WIDTH as f32 * scale
+    }
+
+    pub fn height(scale: f32) -> f32 {
+        constants::ppu::SCREEN_HEIGHT as f32 * scale
+    }
+
+    fn create_image(ctx: &mut Context, pixels: &[u8]) -> Image {
+        Image::from_pixels(
+            ctx,
+            pixels,
+            ImageFormat::Rgba8UnormSrgb,
+            constants::ppu::SCREEN_WIDTH as u32,
+            constants::ppu::SCREEN_HEIGHT as u32,
+        )
+    }
+}